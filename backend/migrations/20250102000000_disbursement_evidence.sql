@@ -0,0 +1,4 @@
+-- Purpose and evidence hashes recorded with each on-chain disbursement
+ALTER TABLE disbursements
+    ADD COLUMN purpose TEXT NOT NULL DEFAULT '',
+    ADD COLUMN evidence_hashes TEXT[] NOT NULL DEFAULT '{}';
@@ -0,0 +1,10 @@
+-- Progress of background workers that read from the chain
+CREATE TABLE chain_cursors (
+    name VARCHAR(50) PRIMARY KEY,
+    cursor TEXT,
+    last_ledger BIGINT NOT NULL DEFAULT 0,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+CREATE INDEX idx_campaigns_contract_campaign_id ON campaigns(contract_campaign_id);
+CREATE INDEX idx_disbursements_tx_hash ON disbursements(tx_hash);
@@ -0,0 +1,18 @@
+-- Differences between the database and the donation contract found by reconciliation
+CREATE TABLE reconciliation_discrepancies (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    campaign_id UUID NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
+    field VARCHAR(50) NOT NULL,
+    db_value TEXT NOT NULL,
+    chain_value TEXT NOT NULL,
+    corrected BOOLEAN NOT NULL DEFAULT false,
+    detected_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    last_seen_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    resolved_at TIMESTAMP WITH TIME ZONE
+);
+
+-- At most one open discrepancy per campaign field
+CREATE UNIQUE INDEX idx_reconciliation_open
+    ON reconciliation_discrepancies(campaign_id, field)
+    WHERE resolved_at IS NULL;
+CREATE INDEX idx_reconciliation_detected_at ON reconciliation_discrepancies(detected_at DESC);
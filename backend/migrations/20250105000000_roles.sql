@@ -0,0 +1,19 @@
+-- Platform-wide roles; any authenticated account without one is a donor
+CREATE TABLE platform_roles (
+    account VARCHAR(56) NOT NULL,
+    role VARCHAR(20) NOT NULL CHECK (role IN ('admin', 'auditor')),
+    granted_by VARCHAR(56),
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    PRIMARY KEY (account, role)
+);
+
+-- Accounts acting for an organization; its wallet address is always an owner
+CREATE TABLE organization_members (
+    org_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    account VARCHAR(56) NOT NULL,
+    role VARCHAR(20) NOT NULL CHECK (role IN ('owner', 'member')),
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    PRIMARY KEY (org_id, account)
+);
+
+CREATE INDEX idx_organization_members_account ON organization_members(account);
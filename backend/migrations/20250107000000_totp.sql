@@ -0,0 +1,17 @@
+-- TOTP second factor for staff accounts. The secret is set when enrolment
+-- starts and only takes effect once a code confirms it (totp_enabled_at).
+ALTER TABLE users
+    ADD COLUMN totp_secret VARCHAR(64),
+    ADD COLUMN totp_enabled_at TIMESTAMP WITH TIME ZONE,
+    -- Last accepted time step, so a code cannot be replayed
+    ADD COLUMN totp_last_step BIGINT;
+
+-- Single-use recovery codes, stored as SHA-256 digests
+CREATE TABLE totp_recovery_codes (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    code_hash VARCHAR(64) NOT NULL,
+    used_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    UNIQUE (user_id, code_hash)
+);
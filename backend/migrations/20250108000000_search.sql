@@ -0,0 +1,18 @@
+-- Full-text search over campaigns and organizations. Names weigh more than
+-- descriptions; the trigram indexes back typo-tolerant name matching.
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+ALTER TABLE campaigns ADD COLUMN search_vector tsvector GENERATED ALWAYS AS (
+    setweight(to_tsvector('english', coalesce(name, '')), 'A') ||
+    setweight(to_tsvector('english', coalesce(description, '')), 'B')
+) STORED;
+
+ALTER TABLE organizations ADD COLUMN search_vector tsvector GENERATED ALWAYS AS (
+    setweight(to_tsvector('english', coalesce(name, '')), 'A') ||
+    setweight(to_tsvector('english', coalesce(description, '')), 'B')
+) STORED;
+
+CREATE INDEX idx_campaigns_search ON campaigns USING GIN (search_vector);
+CREATE INDEX idx_organizations_search ON organizations USING GIN (search_vector);
+CREATE INDEX idx_campaigns_name_trgm ON campaigns USING GIN (name gin_trgm_ops);
+CREATE INDEX idx_organizations_name_trgm ON organizations USING GIN (name gin_trgm_ops);
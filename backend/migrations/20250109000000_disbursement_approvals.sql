@@ -0,0 +1,21 @@
+-- Number of distinct approvals a disbursement needs before it can execute
+ALTER TABLE organizations
+    ADD COLUMN approval_threshold INTEGER NOT NULL DEFAULT 1 CHECK (approval_threshold >= 1);
+
+-- One row per approver, as in database/schema.sql
+CREATE TABLE disbursement_approvals (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    disbursement_id UUID NOT NULL REFERENCES disbursements(id) ON DELETE CASCADE,
+    approver_address VARCHAR(56) NOT NULL,
+    approved_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE(disbursement_id, approver_address)
+);
+
+-- Carry over approvals recorded in the comma-separated approved_by column
+INSERT INTO disbursement_approvals (disbursement_id, approver_address, approved_at)
+SELECT d.id, approver, d.created_at
+FROM disbursements d,
+     LATERAL unnest(string_to_array(d.approved_by, ',')) AS raw(address),
+     LATERAL trim(raw.address) AS approver
+WHERE d.approved_by IS NOT NULL AND approver <> '' AND length(approver) <= 56
+ON CONFLICT DO NOTHING;
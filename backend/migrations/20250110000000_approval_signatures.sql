@@ -0,0 +1,7 @@
+-- Per-disbursement nonce included in the payload approvers sign
+ALTER TABLE disbursements
+    ADD COLUMN approval_nonce VARCHAR(32) NOT NULL DEFAULT replace(uuid_generate_v4()::text, '-', '');
+
+-- Base64 ed25519 signature over the approval payload; NULL for approvals
+-- recorded before signatures were required
+ALTER TABLE disbursement_approvals ADD COLUMN signature TEXT;
@@ -0,0 +1,4 @@
+-- Disbursements whose funds are reserved while the payout is submitted on-chain
+ALTER TABLE disbursements DROP CONSTRAINT disbursements_status_check;
+ALTER TABLE disbursements ADD CONSTRAINT disbursements_status_check
+    CHECK (status IN ('pending', 'approved', 'executing', 'executed', 'rejected'));
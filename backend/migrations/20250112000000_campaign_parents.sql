@@ -0,0 +1,4 @@
+-- Fundraiser pages (contract child campaigns) and the campaign they roll up into
+ALTER TABLE campaigns ADD COLUMN parent_id UUID REFERENCES campaigns(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_campaigns_parent_id ON campaigns(parent_id);
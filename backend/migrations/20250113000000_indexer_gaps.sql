@@ -0,0 +1,3 @@
+-- Ledger ranges the event indexer had to skip are recorded as discrepancies
+-- that belong to no single campaign
+ALTER TABLE reconciliation_discrepancies ALTER COLUMN campaign_id DROP NOT NULL;
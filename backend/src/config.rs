@@ -7,22 +7,153 @@ pub struct Config {
     pub stellar_network: String,
     pub stellar_horizon_url: String,
     pub contract_id: String,
+    pub soroban_rpc_url: String,
+    pub network_passphrase: String,
+    pub signer_secret_key: Option<String>,
+    /// `rpc` or `mock`
+    pub chain_backend: String,
+    pub indexer_poll_interval_secs: u64,
+    /// Ledger to start indexing from when no cursor is saved (defaults to the latest)
+    pub indexer_start_ledger: Option<u32>,
+    /// Seconds between reconciliation runs, 0 disables the periodic job
+    pub reconciliation_interval_secs: u64,
+    /// Let periodic runs overwrite drifted values with the chain's
+    pub reconciliation_auto_correct: bool,
+    /// HMAC secret for access tokens
+    pub jwt_secret: String,
+    /// Lifetime of wallet (SEP-10) access tokens
+    pub jwt_expiry_secs: u64,
+    /// Lifetime of staff access tokens; sessions are extended with refresh tokens
+    pub staff_access_token_expiry_secs: u64,
+    /// Lifetime of staff refresh tokens
+    pub refresh_token_expiry_secs: u64,
+    pub password_reset_expiry_secs: u64,
+    /// `http`, `log` (debug builds only) or `none`
+    pub mailer: String,
+    /// Endpoint the `http` mailer posts messages to
+    pub mailer_url: Option<String>,
+    pub mailer_api_key: Option<String>,
+    pub mailer_from: String,
+    /// How long a TOTP step-up authorizes disbursement approval and execution
+    pub step_up_max_age_secs: u64,
+    /// Domain wallets see in SEP-10 challenges (`<home_domain> auth`)
+    pub home_domain: String,
+    pub web_auth_domain: String,
+    /// Secret seed that signs SEP-10 challenges, defaults to `STELLAR_SECRET_KEY`
+    pub sep10_signing_key: Option<String>,
+    /// Accounts that are platform admins regardless of `platform_roles`
+    pub platform_admins: Vec<String>,
 }
 
 impl Config {
     pub fn from_env() -> Result<Self, env::VarError> {
+        let stellar_network = env::var("STELLAR_NETWORK")
+            .unwrap_or_else(|_| "testnet".to_string());
+        let signer_secret_key = env::var("STELLAR_SECRET_KEY")
+            .ok()
+            .filter(|secret| !secret.is_empty());
+        let home_domain = env::var("HOME_DOMAIN")
+            .unwrap_or_else(|_| "localhost".to_string());
+
         Ok(Self {
             database_url: env::var("DATABASE_URL")?,
             port: env::var("PORT")
                 .unwrap_or_else(|_| "5000".to_string())
                 .parse()
                 .unwrap_or(5000),
-            stellar_network: env::var("STELLAR_NETWORK")
-                .unwrap_or_else(|_| "testnet".to_string()),
             stellar_horizon_url: env::var("STELLAR_HORIZON_URL")
                 .unwrap_or_else(|_| "https://horizon-testnet.stellar.org".to_string()),
             contract_id: env::var("CONTRACT_ID")
                 .unwrap_or_else(|_| "".to_string()),
+            soroban_rpc_url: env::var("SOROBAN_RPC_URL")
+                .unwrap_or_else(|_| "https://soroban-testnet.stellar.org".to_string()),
+            network_passphrase: env::var("STELLAR_NETWORK_PASSPHRASE")
+                .ok()
+                .filter(|passphrase| !passphrase.is_empty())
+                .unwrap_or_else(|| default_network_passphrase(&stellar_network).to_string()),
+            chain_backend: env::var("CHAIN_BACKEND")
+                .unwrap_or_else(|_| "rpc".to_string()),
+            indexer_poll_interval_secs: env::var("INDEXER_POLL_INTERVAL_SECS")
+                .ok()
+                .and_then(|secs| secs.parse().ok())
+                .filter(|secs| *secs > 0)
+                .unwrap_or(5),
+            indexer_start_ledger: env::var("INDEXER_START_LEDGER")
+                .ok()
+                .and_then(|ledger| ledger.parse().ok()),
+            reconciliation_interval_secs: env::var("RECONCILIATION_INTERVAL_SECS")
+                .ok()
+                .and_then(|secs| secs.parse().ok())
+                .unwrap_or(3600),
+            reconciliation_auto_correct: env::var("RECONCILIATION_AUTO_CORRECT")
+                .map(|flag| flag == "true" || flag == "1")
+                .unwrap_or(false),
+            jwt_secret: env::var("JWT_SECRET")?,
+            jwt_expiry_secs: env::var("JWT_EXPIRY_SECS")
+                .ok()
+                .and_then(|secs| secs.parse().ok())
+                .filter(|secs| *secs > 0)
+                .unwrap_or(86400),
+            staff_access_token_expiry_secs: env::var("STAFF_ACCESS_TOKEN_EXPIRY_SECS")
+                .ok()
+                .and_then(|secs| secs.parse().ok())
+                .filter(|secs| *secs > 0)
+                .unwrap_or(900),
+            refresh_token_expiry_secs: env::var("REFRESH_TOKEN_EXPIRY_SECS")
+                .ok()
+                .and_then(|secs| secs.parse().ok())
+                .filter(|secs| *secs > 0)
+                .unwrap_or(30 * 86400),
+            password_reset_expiry_secs: env::var("PASSWORD_RESET_EXPIRY_SECS")
+                .ok()
+                .and_then(|secs| secs.parse().ok())
+                .filter(|secs| *secs > 0)
+                .unwrap_or(3600),
+            mailer: env::var("MAILER")
+                .ok()
+                .filter(|mailer| !mailer.is_empty())
+                .unwrap_or_else(|| if cfg!(debug_assertions) { "log" } else { "none" }.to_string()),
+            mailer_url: env::var("MAILER_URL")
+                .ok()
+                .filter(|url| !url.is_empty()),
+            mailer_api_key: env::var("MAILER_API_KEY")
+                .ok()
+                .filter(|key| !key.is_empty()),
+            mailer_from: env::var("MAILER_FROM")
+                .ok()
+                .filter(|from| !from.is_empty())
+                .unwrap_or_else(|| format!("no-reply@{}", home_domain)),
+            step_up_max_age_secs: env::var("STEP_UP_MAX_AGE_SECS")
+                .ok()
+                .and_then(|secs| secs.parse().ok())
+                .filter(|secs| *secs > 0)
+                .unwrap_or(300),
+            web_auth_domain: env::var("WEB_AUTH_DOMAIN")
+                .ok()
+                .filter(|domain| !domain.is_empty())
+                .unwrap_or_else(|| home_domain.clone()),
+            home_domain,
+            sep10_signing_key: env::var("SEP10_SIGNING_KEY")
+                .ok()
+                .filter(|secret| !secret.is_empty())
+                .or_else(|| signer_secret_key.clone()),
+            signer_secret_key,
+            platform_admins: env::var("PLATFORM_ADMINS")
+                .unwrap_or_default()
+                .split(',')
+                .map(|account| account.trim().to_string())
+                .filter(|account| !account.is_empty())
+                .collect(),
+            stellar_network,
         })
     }
 }
+
+fn default_network_passphrase(network: &str) -> &'static str {
+    match network {
+        "mainnet" | "public" => "Public Global Stellar Network ; September 2015",
+        "futurenet" => "Test SDF Future Network ; October 2022",
+        "standalone" | "local" => "Standalone Network ; February 2017",
+        _ => "Test SDF Network ; September 2015",
+    }
+}
@@ -15,6 +15,9 @@ pub enum AppError {
     
     #[error("Unauthorized: {0}")]
     Unauthorized(String),
+
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
     
     #[error("Bad request: {0}")]
     BadRequest(String),
@@ -24,6 +27,9 @@ pub enum AppError {
     
     #[error("Stellar error: {0}")]
     Stellar(String),
+
+    #[error("Verification failed: {0}")]
+    Verification(String),
 }
 
 impl IntoResponse for AppError {
@@ -32,9 +38,11 @@ impl IntoResponse for AppError {
             AppError::Database(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
             AppError::NotFound(e) => (StatusCode::NOT_FOUND, e),
             AppError::Unauthorized(e) => (StatusCode::UNAUTHORIZED, e),
+            AppError::Forbidden(e) => (StatusCode::FORBIDDEN, e),
             AppError::BadRequest(e) => (StatusCode::BAD_REQUEST, e),
             AppError::Internal(e) => (StatusCode::INTERNAL_SERVER_ERROR, e),
             AppError::Stellar(e) => (StatusCode::BAD_REQUEST, e),
+            AppError::Verification(e) => (StatusCode::UNPROCESSABLE_ENTITY, e),
         };
 
         let body = Json(json!({
@@ -44,5 +52,3 @@ impl IntoResponse for AppError {
         (status, body).into_response()
     }
 }
-
-pub type Result<T> = std::result::Result<T, AppError>;
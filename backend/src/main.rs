@@ -1,11 +1,7 @@
-use axum::{
-    routing::{get, post},
-    Router,
-};
+use axum::{routing::get, Router};
 use sqlx::PgPool;
 use std::sync::Arc;
 use tower_http::cors::{Any, CorsLayer};
-use tracing_subscriber;
 
 mod config;
 mod error;
@@ -14,10 +10,16 @@ mod routes;
 mod services;
 
 use config::Config;
+use services::{
+    chain::{self, ChainBackend},
+    mailer::{self, Mailer},
+};
 
 pub struct AppState {
     pub db: PgPool,
     pub config: Config,
+    pub chain: Arc<dyn ChainBackend>,
+    pub mailer: Arc<dyn Mailer>,
 }
 
 #[tokio::main]
@@ -49,16 +51,38 @@ async fn main() {
 
     tracing::info!("✅ Database migrations completed");
 
+    // Select how the API reaches the donation contract
+    let chain = chain::from_config(&config).expect("Failed to initialise chain backend");
+
+    tracing::info!("✅ Using {} chain backend", config.chain_backend);
+
+    // Select how account emails are delivered
+    let mailer = mailer::from_config(&config).expect("Failed to initialise mailer");
+
+    tracing::info!("✅ Using {} mailer", config.mailer);
+
     // Build application state
     let app_state = Arc::new(AppState {
         db: pool,
         config: config.clone(),
+        chain,
+        mailer,
     });
 
+    // Mirror contract events into the database
+    services::indexer::spawn(app_state.clone());
+
+    // Periodically compare the database with the contract
+    services::reconciliation::spawn(app_state.clone());
+
     // Build router with all routes
     let app = Router::new()
         .route("/", get(root))
         .route("/health", get(health_check))
+        // SEP-10 web authentication
+        .nest("/api/auth", routes::auth::routes())
+        // Staff accounts
+        .nest("/api/users", routes::user::routes())
         // Organization routes
         .nest("/api/organizations", routes::organization::routes())
         // Campaign routes
@@ -67,8 +91,12 @@ async fn main() {
         .nest("/api/donations", routes::donation::routes())
         // Disbursement routes
         .nest("/api/disbursements", routes::disbursement::routes())
+        // Search
+        .nest("/api/search", routes::search::routes())
         // Audit routes
         .nest("/api/audit", routes::audit::routes())
+        // Admin routes
+        .nest("/api/admin", routes::admin::routes())
         .with_state(app_state)
         .layer(
             CorsLayer::new()
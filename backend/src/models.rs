@@ -15,6 +15,8 @@ pub struct Organization {
     pub verified: bool,
     pub email: Option<String>,
     pub description: Option<String>,
+    /// Distinct approvals a disbursement needs
+    pub approval_threshold: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -33,6 +35,22 @@ pub struct UpdateOrganizationRequest {
     pub email: Option<String>,
     pub description: Option<String>,
     pub verified: Option<bool>,
+    pub approval_threshold: Option<i32>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct OrganizationMember {
+    pub org_id: Uuid,
+    pub account: String,
+    pub role: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AddMemberRequest {
+    pub account: String,
+    /// `owner` or `member`
+    pub role: String,
 }
 
 // ===========================
@@ -50,6 +68,8 @@ pub struct Campaign {
     pub status: String,
     pub description: Option<String>,
     pub contract_campaign_id: Option<i32>,
+    /// Campaign this fundraiser page rolls up into
+    pub parent_id: Option<Uuid>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -121,11 +141,35 @@ pub struct Disbursement {
     pub campaign_id: Uuid,
     pub recipient_address: String,
     pub amount: i64,
+    pub purpose: String,
+    pub evidence_hashes: Vec<String>,
     pub status: String,
     pub approved_by: Option<String>,
     pub tx_hash: Option<String>,
     pub created_at: DateTime<Utc>,
     pub executed_at: Option<DateTime<Utc>>,
+    pub approval_nonce: String,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct DisbursementApproval {
+    pub id: Uuid,
+    pub disbursement_id: Uuid,
+    pub approver_address: String,
+    pub approved_at: DateTime<Utc>,
+    /// Base64 SEP-53 signature of the approval payload by the approver
+    pub signature: Option<String>,
+}
+
+/// Disbursement with its approvals and the quorum it needs
+#[derive(Debug, Serialize)]
+pub struct DisbursementDetail {
+    #[serde(flatten)]
+    pub disbursement: Disbursement,
+    pub approval_threshold: i32,
+    /// The exact text each approver signs
+    pub approval_payload: String,
+    pub approvals: Vec<DisbursementApproval>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -133,11 +177,14 @@ pub struct CreateDisbursementRequest {
     pub campaign_id: Uuid,
     pub recipient_address: String,
     pub amount: i64,
+    pub purpose: String,
+    /// Hex-encoded SHA-256 hashes of supporting documents
+    pub evidence_hashes: Vec<String>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct ApproveDisbursementRequest {
-    pub approver_addresses: Vec<String>,
+    pub signature: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -168,21 +215,256 @@ pub struct AuditResponse {
     pub audit_logs: Vec<AuditLog>,
 }
 
+// ===========================
+// Auth Models
+// ===========================
+
+#[derive(Debug, Deserialize)]
+pub struct ChallengeQuery {
+    pub account: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChallengeResponse {
+    pub transaction: String,
+    pub network_passphrase: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TokenRequest {
+    /// Challenge transaction signed by the wallet, base64 XDR
+    pub transaction: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TokenResponse {
+    pub token: String,
+    pub account: String,
+    pub expires_at: u64,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct User {
+    pub id: Uuid,
+    pub email: String,
+    #[serde(skip_serializing)]
+    pub password_hash: String,
+    pub stellar_address: Option<String>,
+    pub org_id: Option<Uuid>,
+    pub org_role: Option<String>,
+    #[serde(skip_serializing)]
+    pub totp_secret: Option<String>,
+    /// Set once TOTP enrolment is confirmed
+    pub totp_enabled_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RegisterRequest {
+    pub email: String,
+    pub password: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LoginRequest {
+    pub email: String,
+    pub password: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RefreshRequest {
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SessionResponse {
+    pub access_token: String,
+    pub refresh_token: String,
+    pub expires_at: u64,
+    pub user: User,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PasswordResetRequest {
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConfirmPasswordResetRequest {
+    pub token: String,
+    pub password: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LinkOrganizationRequest {
+    pub org_id: Uuid,
+    /// `owner` or `member`
+    pub role: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TotpEnrollmentResponse {
+    /// Base32 secret for manual entry
+    pub secret: String,
+    /// `otpauth://` URI to render as a QR code
+    pub provisioning_uri: String,
+}
+
+/// A current TOTP code, or a recovery code where noted
+#[derive(Debug, Deserialize)]
+pub struct TotpCodeRequest {
+    pub code: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RecoveryCodesResponse {
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct PlatformRoleGrant {
+    pub account: String,
+    pub role: String,
+    pub granted_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GrantRoleRequest {
+    pub account: String,
+    /// `admin` or `auditor`
+    pub role: String,
+}
+
+// ===========================
+// Reconciliation Models
+// ===========================
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ReconciliationDiscrepancy {
+    pub id: Uuid,
+    /// `None` for an `indexer_gap`, which is not tied to one campaign
+    pub campaign_id: Option<Uuid>,
+    pub field: String,
+    pub db_value: String,
+    pub chain_value: String,
+    pub corrected: bool,
+    pub detected_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ReconciliationReport {
+    pub campaigns_checked: usize,
+    pub discrepancies: usize,
+    pub corrected: usize,
+    pub failed: usize,
+    /// Executing disbursements whose submitted payout was found on-chain
+    pub disbursements_settled: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RunReconciliationRequest {
+    /// Overwrite the database with the chain's values where possible
+    #[serde(default)]
+    pub auto_correct: bool,
+}
+
+// ===========================
+// Search Models
+// ===========================
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct SearchResult {
+    /// `campaign` or `organization`
+    pub kind: String,
+    pub id: Uuid,
+    pub name: String,
+    /// HTML-escaped excerpt with matches wrapped in `<mark>`
+    pub snippet: Option<String>,
+    pub rank: f32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchResponse {
+    pub query: String,
+    /// True when nothing matched exactly and results come from fuzzy name matching
+    pub fuzzy: bool,
+    pub results: Vec<SearchResult>,
+}
+
+// ===========================
+// Pagination
+// ===========================
+
+/// Envelope of every list endpoint
+#[derive(Debug, Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Pass as `cursor` to fetch the next page; absent on the last page
+    pub next_cursor: Option<String>,
+    pub has_more: bool,
+}
+
 // ===========================
 // Query Parameters
 // ===========================
 
+/// `cursor` and `limit` of list endpoints without filters
+#[derive(Debug, Deserialize)]
+pub struct PageParams {
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct CampaignQueryParams {
     pub org_id: Option<Uuid>,
     pub status: Option<String>,
+    pub min_goal: Option<i64>,
+    pub max_goal: Option<i64>,
+    pub min_raised: Option<i64>,
+    pub max_raised: Option<i64>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    pub deadline_after: Option<DateTime<Utc>>,
+    pub deadline_before: Option<DateTime<Utc>>,
+    /// Only campaigns of verified organizations
+    pub verified_only: Option<bool>,
+    /// Comma-separated keys, `-` prefix for descending, e.g. `-raised_amount,deadline`
+    pub sort: Option<String>,
+    pub cursor: Option<String>,
     pub limit: Option<i64>,
-    pub offset: Option<i64>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct DonationQueryParams {
     pub donor_address: Option<String>,
+    pub min_amount: Option<i64>,
+    pub max_amount: Option<i64>,
+    pub donated_after: Option<DateTime<Utc>>,
+    pub donated_before: Option<DateTime<Utc>>,
+    /// Comma-separated keys, `-` prefix for descending, e.g. `-amount`
+    pub sort: Option<String>,
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReconciliationQueryParams {
+    pub campaign_id: Option<Uuid>,
+    pub include_resolved: Option<bool>,
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SearchQueryParams {
+    pub q: String,
+    /// `campaign` or `organization`; both when omitted
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
     pub limit: Option<i64>,
-    pub offset: Option<i64>,
 }
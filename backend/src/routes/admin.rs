@@ -0,0 +1,172 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json, Router,
+    routing::{delete, get},
+};
+use sqlx::{Postgres, QueryBuilder};
+use std::sync::Arc;
+
+use crate::{
+    error::AppError,
+    models::{
+        GrantRoleRequest, PageParams, PlatformRoleGrant, ReconciliationDiscrepancy,
+        ReconciliationQueryParams, RunReconciliationRequest,
+    },
+    services::{
+        listing::{FieldKind, Listing, SortField},
+        reconciliation,
+        roles::{PlatformRole, Principal},
+    },
+    AppState,
+};
+
+/// Paging of `GET /api/admin/reconciliation`
+const DISCREPANCIES: Listing = Listing {
+    fields: &[SortField::new("detected_at", "detected_at", FieldKind::Time)],
+    default_sort: "-detected_at",
+    tiebreak: &[SortField::new("id", "id", FieldKind::Uuid)],
+    default_limit: 50,
+};
+
+/// Paging of `GET /api/admin/roles`
+const ROLES: Listing = Listing {
+    fields: &[SortField::new("created_at", "created_at", FieldKind::Time)],
+    default_sort: "created_at",
+    tiebreak: &[
+        SortField::new("account", "account", FieldKind::Text),
+        SortField::new("role", "role", FieldKind::Text),
+    ],
+    default_limit: 50,
+};
+
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/reconciliation", get(list_discrepancies).post(run_reconciliation))
+        .route("/roles", get(list_roles).post(grant_role))
+        .route("/roles/:account/:role", delete(revoke_role))
+}
+
+/// List discrepancies between the database and the contract (open ones by default)
+async fn list_discrepancies(
+    State(state): State<Arc<AppState>>,
+    principal: Principal,
+    Query(params): Query<ReconciliationQueryParams>,
+) -> Result<impl IntoResponse, AppError> {
+    principal.require_auditor()?;
+
+    let page = DISCREPANCIES.page(None, params.cursor.as_deref(), params.limit)?;
+
+    let mut query = QueryBuilder::<Postgres>::new("SELECT * FROM reconciliation_discrepancies WHERE TRUE");
+    if let Some(campaign_id) = params.campaign_id {
+        query.push(" AND campaign_id = ").push_bind(campaign_id);
+    }
+    if !params.include_resolved.unwrap_or(false) {
+        query.push(" AND resolved_at IS NULL");
+    }
+    page.push_keyset(&mut query);
+
+    let discrepancies = query
+        .build_query_as::<ReconciliationDiscrepancy>()
+        .fetch_all(&state.db)
+        .await?;
+
+    Ok(Json(page.finish(discrepancies)?))
+}
+
+/// Reconcile every on-chain campaign now and return a summary
+async fn run_reconciliation(
+    State(state): State<Arc<AppState>>,
+    principal: Principal,
+    Json(payload): Json<RunReconciliationRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    principal.require_admin()?;
+
+    let report = reconciliation::reconcile(&state, payload.auto_correct).await?;
+
+    Ok(Json(report))
+}
+
+/// List platform admins and auditors granted through the API
+async fn list_roles(
+    State(state): State<Arc<AppState>>,
+    principal: Principal,
+    Query(params): Query<PageParams>,
+) -> Result<impl IntoResponse, AppError> {
+    principal.require_admin()?;
+
+    let page = ROLES.page(None, params.cursor.as_deref(), params.limit)?;
+
+    let mut query = QueryBuilder::<Postgres>::new("SELECT * FROM platform_roles WHERE TRUE");
+    page.push_keyset(&mut query);
+
+    let grants = query
+        .build_query_as::<PlatformRoleGrant>()
+        .fetch_all(&state.db)
+        .await?;
+
+    Ok(Json(page.finish(grants)?))
+}
+
+/// Grant a platform role to an account
+async fn grant_role(
+    State(state): State<Arc<AppState>>,
+    principal: Principal,
+    Json(payload): Json<GrantRoleRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    principal.require_admin()?;
+
+    if PlatformRole::parse(&payload.role).is_none() {
+        return Err(AppError::BadRequest(
+            "Role must be admin or auditor".to_string(),
+        ));
+    }
+    if stellar_strkey::ed25519::PublicKey::from_string(&payload.account).is_err() {
+        return Err(AppError::BadRequest(format!(
+            "Invalid Stellar account {}",
+            payload.account
+        )));
+    }
+
+    let grant = sqlx::query_as::<_, PlatformRoleGrant>(
+        r#"
+        INSERT INTO platform_roles (account, role, granted_by)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (account, role) DO UPDATE SET granted_by = EXCLUDED.granted_by
+        RETURNING *
+        "#,
+    )
+    .bind(&payload.account)
+    .bind(&payload.role)
+    .bind(&principal.account)
+    .fetch_one(&state.db)
+    .await?;
+
+    tracing::info!("{} granted {} to {}", principal.account, payload.role, payload.account);
+
+    Ok((StatusCode::CREATED, Json(grant)))
+}
+
+/// Revoke a platform role
+async fn revoke_role(
+    State(state): State<Arc<AppState>>,
+    principal: Principal,
+    Path((account, role)): Path<(String, String)>,
+) -> Result<impl IntoResponse, AppError> {
+    principal.require_admin()?;
+
+    let revoked = sqlx::query("DELETE FROM platform_roles WHERE account = $1 AND role = $2")
+        .bind(&account)
+        .bind(&role)
+        .execute(&state.db)
+        .await?;
+
+    if revoked.rows_affected() == 0 {
+        return Err(AppError::NotFound("Role grant not found".to_string()));
+    }
+
+    tracing::info!("{} revoked {} from {}", principal.account, role, account);
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,87 @@
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+    Json, Router,
+    routing::get,
+};
+use std::sync::Arc;
+
+use crate::{
+    error::AppError,
+    models::{ChallengeQuery, ChallengeResponse, TokenRequest, TokenResponse},
+    services::{
+        accounts,
+        auth::{issue_token, unix_now},
+        roles::Principal,
+        sep10::{build_challenge, verify_challenge, ChallengeContext},
+        soroban_rpc::StellarSigner,
+    },
+    AppState,
+};
+
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", get(get_challenge).post(exchange_token))
+        .route("/me", get(get_me))
+}
+
+/// Issue a SEP-10 challenge transaction for a wallet to sign
+async fn get_challenge(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<ChallengeQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let server = signing_key(&state)?;
+    let transaction = build_challenge(&server, &params.account, &context(&state), unix_now())?;
+
+    Ok(Json(ChallengeResponse {
+        transaction,
+        network_passphrase: state.config.network_passphrase.clone(),
+    }))
+}
+
+/// Exchange a signed challenge for an access token
+async fn exchange_token(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<TokenRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let server = signing_key(&state)?;
+    let now = unix_now();
+    let account = verify_challenge(
+        server.public_key(),
+        &payload.transaction,
+        &context(&state),
+        now,
+    )?;
+    let user_id = accounts::totp_user_for_address(&state.db, &account).await?;
+    let (token, claims) = issue_token(&state.config, &account, user_id, None, now)?;
+
+    tracing::info!("Issued access token to {}", account);
+
+    Ok(Json(TokenResponse {
+        token,
+        account,
+        expires_at: claims.exp,
+    }))
+}
+
+/// Roles of the authenticated account
+async fn get_me(principal: Principal) -> Result<impl IntoResponse, AppError> {
+    Ok(Json(principal))
+}
+
+pub(crate) fn signing_key(state: &AppState) -> Result<StellarSigner, AppError> {
+    let secret = state
+        .config
+        .sep10_signing_key
+        .as_deref()
+        .ok_or_else(|| AppError::Internal("SEP10_SIGNING_KEY is not configured".to_string()))?;
+    StellarSigner::from_secret(secret)
+}
+
+pub(crate) fn context(state: &AppState) -> ChallengeContext<'_> {
+    ChallengeContext {
+        home_domain: &state.config.home_domain,
+        web_auth_domain: &state.config.web_auth_domain,
+        network_passphrase: &state.config.network_passphrase,
+    }
+}
@@ -1,24 +1,40 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     response::IntoResponse,
     Json, Router,
-    routing::{get, post, patch},
+    routing::{get, post},
 };
 use serde_json::json;
+use sqlx::{PgConnection, Postgres, QueryBuilder};
 use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::{
     error::AppError,
     models::{
-        ApproveDisbursementRequest, CreateDisbursementRequest, 
-        Disbursement, ExecuteDisbursementRequest,
+        ApproveDisbursementRequest, CreateDisbursementRequest, Disbursement, DisbursementApproval,
+        DisbursementDetail, ExecuteDisbursementRequest, PageParams,
+    },
+    services::{
+        approvals::{approval_payload, verify_approval},
+        auth::unix_now,
+        chain,
+        ledger,
+        listing::{FieldKind, Listing, SortField},
+        roles::Principal,
     },
-    services::stellar_service,
     AppState,
 };
 
+/// Paging of `GET /api/disbursements/campaign/:campaign_id`
+const DISBURSEMENTS: Listing = Listing {
+    fields: &[SortField::new("created_at", "created_at", FieldKind::Time)],
+    default_sort: "-created_at",
+    tiebreak: &[SortField::new("id", "id", FieldKind::Uuid)],
+    default_limit: 50,
+};
+
 pub fn routes() -> Router<Arc<AppState>> {
     Router::new()
         .route("/", post(create_disbursement))
@@ -31,14 +47,14 @@ pub fn routes() -> Router<Arc<AppState>> {
 /// Create a new disbursement request
 async fn create_disbursement(
     State(state): State<Arc<AppState>>,
+    principal: Principal,
     Json(payload): Json<CreateDisbursementRequest>,
 ) -> Result<impl IntoResponse, AppError> {
     // Verify campaign exists
     let campaign = sqlx::query!(
         r#"
-        SELECT c.id, c.raised_amount, c.contract_campaign_id, o.wallet_address
+        SELECT c.id, c.org_id, c.raised_amount, c.contract_campaign_id
         FROM campaigns c
-        INNER JOIN organizations o ON c.org_id = o.id
         WHERE c.id = $1
         "#,
         payload.campaign_id
@@ -47,24 +63,51 @@ async fn create_disbursement(
     .await?
     .ok_or_else(|| AppError::NotFound("Campaign not found".to_string()))?;
 
+    principal.require_org_member(campaign.org_id)?;
+
     // Check if sufficient funds
-    if payload.amount > campaign.raised_amount {
+    if payload.amount > campaign.raised_amount.unwrap_or(0) {
         return Err(AppError::BadRequest(
             "Insufficient funds in campaign".to_string(),
         ));
     }
 
+    // Mirror the contract's limits so execution cannot fail on them later
+    if payload.purpose.trim().is_empty() || payload.purpose.len() > 256 {
+        return Err(AppError::BadRequest(
+            "Disbursement purpose must be between 1 and 256 bytes".to_string(),
+        ));
+    }
+
+    if payload.evidence_hashes.is_empty() || payload.evidence_hashes.len() > 10 {
+        return Err(AppError::BadRequest(
+            "Between 1 and 10 evidence hashes are required".to_string(),
+        ));
+    }
+
+    if payload
+        .evidence_hashes
+        .iter()
+        .any(|hash| hash.len() != 64 || !hash.bytes().all(|b| b.is_ascii_hexdigit()))
+    {
+        return Err(AppError::BadRequest(
+            "Evidence hashes must be hex-encoded SHA-256 digests".to_string(),
+        ));
+    }
+
     // Insert disbursement
     let disbursement = sqlx::query_as::<_, Disbursement>(
         r#"
-        INSERT INTO disbursements (campaign_id, recipient_address, amount, status)
-        VALUES ($1, $2, $3, 'pending')
+        INSERT INTO disbursements (campaign_id, recipient_address, amount, purpose, evidence_hashes, status)
+        VALUES ($1, $2, $3, $4, $5, 'pending')
         RETURNING *
         "#,
     )
     .bind(payload.campaign_id)
     .bind(&payload.recipient_address)
     .bind(payload.amount)
+    .bind(&payload.purpose)
+    .bind(&payload.evidence_hashes)
     .fetch_one(&state.db)
     .await?;
 
@@ -78,11 +121,12 @@ async fn create_disbursement(
     .bind("disbursement")
     .bind(disbursement.id)
     .bind("created")
-    .bind(&campaign.wallet_address.unwrap_or_default())
+    .bind(&principal.account)
     .bind(json!({
         "campaign_id": payload.campaign_id,
         "recipient_address": &payload.recipient_address,
-        "amount": payload.amount
+        "amount": payload.amount,
+        "purpose": &payload.purpose
     }))
     .execute(&state.db)
     .await;
@@ -90,7 +134,7 @@ async fn create_disbursement(
     Ok((StatusCode::CREATED, Json(disbursement)))
 }
 
-/// Get disbursement by ID
+/// Get disbursement by ID, with its approvals
 async fn get_disbursement(
     State(state): State<Arc<AppState>>,
     Path(id): Path<Uuid>,
@@ -103,129 +147,92 @@ async fn get_disbursement(
     .await?
     .ok_or_else(|| AppError::NotFound("Disbursement not found".to_string()))?;
 
-    Ok(Json(disbursement))
+    Ok(Json(disbursement_detail(&mut *state.db.acquire().await?, disbursement).await?))
 }
 
-/// Approve a disbursement (multi-signature logic)
+/// Record the caller's signed approval; the disbursement becomes approved once
+/// the organization's approval threshold is met
 async fn approve_disbursement(
     State(state): State<Arc<AppState>>,
+    principal: Principal,
     Path(id): Path<Uuid>,
     Json(payload): Json<ApproveDisbursementRequest>,
 ) -> Result<impl IntoResponse, AppError> {
-    // Get disbursement
+    let mut tx = state.db.begin().await?;
+
+    // Lock the disbursement so concurrent approvals count each other
     let disbursement = sqlx::query_as::<_, Disbursement>(
-        "SELECT * FROM disbursements WHERE id = $1"
+        "SELECT * FROM disbursements WHERE id = $1 FOR UPDATE"
     )
     .bind(id)
-    .fetch_optional(&state.db)
+    .fetch_optional(&mut *tx)
     .await?
     .ok_or_else(|| AppError::NotFound("Disbursement not found".to_string()))?;
 
+    principal.require_org_owner(campaign_org_id(&state, disbursement.campaign_id).await?)?;
+    principal.require_step_up(unix_now(), state.config.step_up_max_age_secs)?;
+
     if disbursement.status != "pending" {
         return Err(AppError::BadRequest(
             "Disbursement is not in pending status".to_string(),
         ));
     }
 
-    // Update approved_by with approver addresses
-    let approved_by = payload.approver_addresses.join(",");
-    
-    let updated = sqlx::query_as::<_, Disbursement>(
+    // The caller's Stellar key must have signed this disbursement's terms
+    verify_approval(
+        &principal.account,
+        &approval_payload(
+            id,
+            &disbursement.recipient_address,
+            disbursement.amount,
+            &disbursement.approval_nonce,
+        ),
+        &payload.signature,
+    )?;
+
+    let inserted = sqlx::query_scalar::<_, Uuid>(
         r#"
-        UPDATE disbursements
-        SET approved_by = $1, status = 'approved'
-        WHERE id = $2
-        RETURNING *
+        INSERT INTO disbursement_approvals (disbursement_id, approver_address, signature)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (disbursement_id, approver_address) DO NOTHING
+        RETURNING id
         "#,
     )
-    .bind(&approved_by)
     .bind(id)
-    .fetch_one(&state.db)
+    .bind(&principal.account)
+    .bind(payload.signature.trim())
+    .fetch_optional(&mut *tx)
     .await?;
 
-    // Log audit
-    for approver in &payload.approver_addresses {
-        let _ = sqlx::query(
-            r#"
-            INSERT INTO audit_logs (entity_type, entity_id, action, actor_address, details)
-            VALUES ($1, $2, $3, $4, $5)
-            "#,
-        )
-        .bind("disbursement")
-        .bind(id)
-        .bind("approved")
-        .bind(approver)
-        .bind(json!({"approver": approver}))
-        .execute(&state.db)
-        .await;
-    }
-
-    Ok(Json(updated))
-}
-
-/// Execute an approved disbursement on-chain
-async fn execute_disbursement(
-    State(state): State<Arc<AppState>>,
-    Path(id): Path<Uuid>,
-    Json(payload): Json<ExecuteDisbursementRequest>,
-) -> Result<impl IntoResponse, AppError> {
-    // Get disbursement
-    let disbursement = sqlx::query!(
-        r#"
-        SELECT d.*, c.contract_campaign_id
-        FROM disbursements d
-        INNER JOIN campaigns c ON d.campaign_id = c.id
-        WHERE d.id = $1
-        "#,
-        id
-    )
-    .fetch_optional(&state.db)
-    .await?
-    .ok_or_else(|| AppError::NotFound("Disbursement not found".to_string()))?;
-
-    if disbursement.status != "approved" {
+    if inserted.is_none() {
         return Err(AppError::BadRequest(
-            "Disbursement must be approved first".to_string(),
+            "You have already approved this disbursement".to_string(),
         ));
     }
 
-    // Execute disbursement on blockchain
-    if let Some(contract_campaign_id) = disbursement.contract_campaign_id {
-        stellar_service::execute_disbursement_on_chain(
-            &state.config,
-            contract_campaign_id as u32,
-            &disbursement.recipient_address,
-            disbursement.amount,
-        )
-        .await?;
-    }
-
-    // Update disbursement status and tx_hash
-    let updated = sqlx::query_as::<_, Disbursement>(
+    // Keep the legacy approved_by list in step and flip the status at quorum
+    let disbursement = sqlx::query_as::<_, Disbursement>(
         r#"
-        UPDATE disbursements
-        SET status = 'executed', tx_hash = $1, executed_at = NOW()
-        WHERE id = $2
-        RETURNING *
+        UPDATE disbursements d
+        SET approved_by = a.approvers,
+            status = CASE WHEN a.approvals >= o.approval_threshold THEN 'approved' ELSE d.status END
+        FROM (
+            SELECT string_agg(approver_address, ',' ORDER BY approved_at, id) AS approvers,
+                   COUNT(*) AS approvals
+            FROM disbursement_approvals
+            WHERE disbursement_id = $1
+        ) a, campaigns c
+        INNER JOIN organizations o ON o.id = c.org_id
+        WHERE d.id = $1 AND c.id = d.campaign_id
+        RETURNING d.*
         "#,
     )
-    .bind(&payload.tx_hash)
     .bind(id)
-    .fetch_one(&state.db)
+    .fetch_one(&mut *tx)
     .await?;
 
-    // Update campaign raised amount (decrease)
-    let _ = sqlx::query!(
-        r#"
-        UPDATE campaigns
-        SET raised_amount = raised_amount - $1
-        WHERE id = $2
-        "#,
-        disbursement.amount,
-        disbursement.campaign_id
-    )
-    .execute(&state.db)
-    .await;
+    let detail = disbursement_detail(&mut tx, disbursement).await?;
+    tx.commit().await?;
 
     // Log audit
     let _ = sqlx::query(
@@ -236,29 +243,128 @@ async fn execute_disbursement(
     )
     .bind("disbursement")
     .bind(id)
-    .bind("executed")
-    .bind(&disbursement.recipient_address)
+    .bind("approved")
+    .bind(&principal.account)
     .bind(json!({
-        "tx_hash": &payload.tx_hash,
-        "amount": disbursement.amount
+        "approver": &principal.account,
+        "signature": payload.signature.trim(),
+        "approvals": detail.approvals.len(),
+        "threshold": detail.approval_threshold,
+        "quorum_reached": detail.disbursement.status == "approved"
     }))
     .execute(&state.db)
     .await;
 
+    Ok(Json(detail))
+}
+
+/// Execute an approved disbursement on-chain
+async fn execute_disbursement(
+    State(state): State<Arc<AppState>>,
+    principal: Principal,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<ExecuteDisbursementRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let (org_id, org_wallet, contract_campaign_id) =
+        sqlx::query_as::<_, (Uuid, String, Option<i32>)>(
+            r#"
+            SELECT c.org_id, o.wallet_address, c.contract_campaign_id
+            FROM disbursements d
+            INNER JOIN campaigns c ON d.campaign_id = c.id
+            INNER JOIN organizations o ON c.org_id = o.id
+            WHERE d.id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Disbursement not found".to_string()))?;
+
+    principal.require_org_owner(org_id)?;
+    principal.require_step_up(unix_now(), state.config.step_up_max_age_secs)?;
+    if contract_campaign_id.is_some() {
+        chain::require_signer(state.chain.as_ref(), &org_wallet)?;
+    }
+
+    // Reserve the funds under row locks, then release them on-chain, so
+    // concurrent executions cannot overdraw the campaign
+    let updated = ledger::execute_disbursement(
+        &state.db,
+        state.chain.as_ref(),
+        id,
+        &payload.tx_hash,
+        &principal.account,
+    )
+    .await?;
+
     Ok(Json(updated))
 }
 
-/// Get all disbursements for a campaign
+/// Get disbursements for a campaign, newest first, one page at a time
 async fn get_campaign_disbursements(
     State(state): State<Arc<AppState>>,
     Path(campaign_id): Path<Uuid>,
+    Query(params): Query<PageParams>,
 ) -> Result<impl IntoResponse, AppError> {
-    let disbursements = sqlx::query_as::<_, Disbursement>(
-        "SELECT * FROM disbursements WHERE campaign_id = $1 ORDER BY created_at DESC"
+    let page = DISBURSEMENTS.page(None, params.cursor.as_deref(), params.limit)?;
+
+    let mut query = QueryBuilder::<Postgres>::new("SELECT * FROM disbursements WHERE campaign_id = ");
+    query.push_bind(campaign_id);
+    page.push_keyset(&mut query);
+
+    let disbursements = query
+        .build_query_as::<Disbursement>()
+        .fetch_all(&state.db)
+        .await?;
+
+    Ok(Json(page.finish(disbursements)?))
+}
+
+/// Attach the approvals recorded so far and the quorum the organization requires
+async fn disbursement_detail(
+    conn: &mut PgConnection,
+    disbursement: Disbursement,
+) -> Result<DisbursementDetail, AppError> {
+    let approval_threshold = sqlx::query_scalar::<_, i32>(
+        r#"
+        SELECT o.approval_threshold
+        FROM campaigns c
+        INNER JOIN organizations o ON o.id = c.org_id
+        WHERE c.id = $1
+        "#,
+    )
+    .bind(disbursement.campaign_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let approvals = sqlx::query_as::<_, DisbursementApproval>(
+        r#"
+        SELECT * FROM disbursement_approvals
+        WHERE disbursement_id = $1
+        ORDER BY approved_at, id
+        "#,
     )
-    .bind(campaign_id)
-    .fetch_all(&state.db)
+    .bind(disbursement.id)
+    .fetch_all(&mut *conn)
     .await?;
 
-    Ok(Json(disbursements))
+    Ok(DisbursementDetail {
+        approval_payload: approval_payload(
+            disbursement.id,
+            &disbursement.recipient_address,
+            disbursement.amount,
+            &disbursement.approval_nonce,
+        ),
+        disbursement,
+        approval_threshold,
+        approvals,
+    })
+}
+
+async fn campaign_org_id(state: &AppState, campaign_id: Uuid) -> Result<Uuid, AppError> {
+    sqlx::query_scalar::<_, Uuid>("SELECT org_id FROM campaigns WHERE id = $1")
+        .bind(campaign_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Campaign not found".to_string()))
 }
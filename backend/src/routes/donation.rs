@@ -5,32 +5,48 @@ use axum::{
     Json, Router,
     routing::{get, post},
 };
-use serde_json::json;
+use sqlx::{Postgres, QueryBuilder};
 use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::{
     error::AppError,
     models::{CreateDonationRequest, Donation, DonationQueryParams},
-    services::stellar_service,
+    services::{
+        chain, ledger,
+        listing::{self, FieldKind, Listing, SortField},
+    },
     AppState,
 };
 
+/// Sorting and paging of `GET /api/donations/:campaign_id`
+const DONATIONS: Listing = Listing {
+    fields: &[
+        SortField::new("timestamp", "timestamp", FieldKind::Time),
+        SortField::new("amount", "amount", FieldKind::Int),
+    ],
+    default_sort: "-timestamp",
+    tiebreak: &[SortField::new("id", "id", FieldKind::Uuid)],
+    default_limit: 100,
+};
+
 pub fn routes() -> Router<Arc<AppState>> {
     Router::new()
         .route("/", post(create_donation))
         .route("/:campaign_id", get(get_campaign_donations))
 }
 
-/// Record a donation and trigger on-chain donate
+/// Record a donation after verifying its on-chain transaction
 async fn create_donation(
     State(state): State<Arc<AppState>>,
     Json(payload): Json<CreateDonationRequest>,
 ) -> Result<impl IntoResponse, AppError> {
+    let tx_hash = chain::normalize_tx_hash(&payload.tx_hash)?;
+
     // Verify campaign exists and is active
     let campaign = sqlx::query!(
         r#"
-        SELECT c.id, c.contract_campaign_id, c.status, c.goal_amount, c.raised_amount
+        SELECT c.id, c.contract_campaign_id, c.status
         FROM campaigns c
         WHERE c.id = $1
         "#,
@@ -46,126 +62,71 @@ async fn create_donation(
         ));
     }
 
-    // Process donation on blockchain
-    if let Some(contract_campaign_id) = campaign.contract_campaign_id {
-        stellar_service::process_donation_on_chain(
-            &state.config,
-            contract_campaign_id as u32,
-            &payload.donor_address,
-            payload.amount,
-        )
-        .await?;
-    }
+    let contract_campaign_id = campaign.contract_campaign_id.ok_or_else(|| {
+        AppError::BadRequest("Campaign has no on-chain counterpart".to_string())
+    })?;
 
-    // Insert donation into database
-    let donation = sqlx::query_as::<_, Donation>(
-        r#"
-        INSERT INTO donations (campaign_id, donor_address, amount, tx_hash)
-        VALUES ($1, $2, $3, $4)
-        RETURNING *
-        "#,
+    let already_recorded = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM donations WHERE tx_hash = $1)"
     )
-    .bind(payload.campaign_id)
-    .bind(&payload.donor_address)
-    .bind(payload.amount)
-    .bind(&payload.tx_hash)
+    .bind(&tx_hash)
     .fetch_one(&state.db)
     .await?;
 
-    // Update campaign raised amount
-    let new_raised = campaign.raised_amount + payload.amount;
-    let _ = sqlx::query!(
-        r#"
-        UPDATE campaigns
-        SET raised_amount = $1, updated_at = NOW()
-        WHERE id = $2
-        "#,
-        new_raised,
-        payload.campaign_id
-    )
-    .execute(&state.db)
-    .await;
-
-    // Check if goal reached
-    let status = if new_raised >= campaign.goal_amount {
-        "completed"
-    } else {
-        "active"
-    };
-    let _ = sqlx::query!(
-        "UPDATE campaigns SET status = $1 WHERE id = $2",
-        status,
-        payload.campaign_id
+    if already_recorded {
+        return Err(AppError::BadRequest(
+            "Donation transaction already recorded".to_string(),
+        ));
+    }
+
+    // The donor submits the donate call themselves; only record it once the
+    // network confirms it matches this request
+    chain::verify_donation(
+        state.chain.as_ref(),
+        &tx_hash,
+        contract_campaign_id as u32,
+        &payload.donor_address,
+        payload.amount,
     )
-    .execute(&state.db)
-    .await;
+    .await?;
 
-    // Log audit
-    let _ = sqlx::query(
-        r#"
-        INSERT INTO audit_logs (entity_type, entity_id, action, actor_address, details)
-        VALUES ($1, $2, $3, $4, $5)
-        "#,
+    // Insert the donation, credit the campaign and log it atomically
+    let donation = ledger::record_donation(
+        &state.db,
+        payload.campaign_id,
+        &payload.donor_address,
+        payload.amount,
+        &tx_hash,
     )
-    .bind("donation")
-    .bind(donation.id)
-    .bind("created")
-    .bind(&payload.donor_address)
-    .bind(json!({
-        "campaign_id": payload.campaign_id,
-        "amount": payload.amount,
-        "tx_hash": &payload.tx_hash
-    }))
-    .execute(&state.db)
-    .await;
+    .await?;
 
     Ok((StatusCode::CREATED, Json(donation)))
 }
 
-/// Get donation list for a campaign
+/// Get donation list for a campaign with optional filtering and sorting, one page at a time
 async fn get_campaign_donations(
     State(state): State<Arc<AppState>>,
     Path(campaign_id): Path<Uuid>,
     Query(params): Query<DonationQueryParams>,
 ) -> Result<impl IntoResponse, AppError> {
-    let limit = params.limit.unwrap_or(100);
-    let offset = params.offset.unwrap_or(0);
+    let page = DONATIONS.page(params.sort.as_deref(), params.cursor.as_deref(), params.limit)?;
 
-    let mut query = format!(
-        r#"
-        SELECT * FROM donations
-        WHERE campaign_id = '{}'
-        "#,
-        campaign_id
-    );
+    let mut query = QueryBuilder::<Postgres>::new("SELECT * FROM donations WHERE campaign_id = ");
+    query.push_bind(campaign_id);
 
     if let Some(donor_address) = params.donor_address {
-        query.push_str(&format!(" AND donor_address = '{}'", donor_address));
+        query.push(" AND donor_address = ").push_bind(donor_address);
     }
 
-    query.push_str(&format!(
-        " ORDER BY timestamp DESC LIMIT {} OFFSET {}",
-        limit, offset
-    ));
+    listing::push_range(&mut query, "amount", "amount", params.min_amount, params.max_amount)?;
+    listing::push_range(&mut query, "donation date", "timestamp", params.donated_after, params.donated_before)?;
+
+    page.push_keyset(&mut query);
 
-    let donations = sqlx::query_as::<_, Donation>(&query)
+    let donations = query
+        .build_query_as::<Donation>()
         .fetch_all(&state.db)
         .await?;
 
-    // Get total count
-    let total = sqlx::query!(
-        "SELECT COUNT(*) as count FROM donations WHERE campaign_id = $1",
-        campaign_id
-    )
-    .fetch_one(&state.db)
-    .await?;
-
-    let response = json!({
-        "donations": donations,
-        "total": total.count.unwrap_or(0),
-        "limit": limit,
-        "offset": offset
-    });
-
-    Ok(Json(response))
+    Ok(Json(page.finish(donations)?))
 }
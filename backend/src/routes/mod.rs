@@ -3,3 +3,7 @@ pub mod campaign;
 pub mod donation;
 pub mod disbursement;
 pub mod audit;
+pub mod admin;
+pub mod auth;
+pub mod user;
+pub mod search;
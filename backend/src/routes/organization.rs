@@ -1,20 +1,45 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     response::IntoResponse,
     Json, Router,
-    routing::{get, post, patch},
+    routing::{delete, get, post, patch},
 };
 use serde_json::json;
+use sqlx::{Postgres, QueryBuilder};
 use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::{
     error::AppError,
-    models::{CreateOrganizationRequest, Organization, UpdateOrganizationRequest},
+    models::{
+        AddMemberRequest, CreateOrganizationRequest, Organization, OrganizationMember,
+        PageParams, UpdateOrganizationRequest,
+    },
+    services::{
+        auth::unix_now,
+        listing::{FieldKind, Listing, SortField},
+        roles::{OrgRole, Principal},
+    },
     AppState,
 };
 
+/// Paging of `GET /api/organizations`
+const ORGANIZATIONS: Listing = Listing {
+    fields: &[SortField::new("created_at", "created_at", FieldKind::Time)],
+    default_sort: "-created_at",
+    tiebreak: &[SortField::new("id", "id", FieldKind::Uuid)],
+    default_limit: 50,
+};
+
+/// Paging of `GET /api/organizations/:id/members`
+const MEMBERS: Listing = Listing {
+    fields: &[SortField::new("created_at", "created_at", FieldKind::Time)],
+    default_sort: "created_at",
+    tiebreak: &[SortField::new("account", "account", FieldKind::Text)],
+    default_limit: 50,
+};
+
 pub fn routes() -> Router<Arc<AppState>> {
     Router::new()
         .route("/", post(create_organization))
@@ -22,13 +47,22 @@ pub fn routes() -> Router<Arc<AppState>> {
         .route("/:id", get(get_organization))
         .route("/:id", patch(update_organization))
         .route("/wallet/:wallet_address", get(get_organization_by_wallet))
+        .route("/:id/members", get(list_members))
+        .route("/:id/members", post(add_member))
+        .route("/:id/members/:account", delete(remove_member))
 }
 
 /// Create a new organization (NGO)
 async fn create_organization(
     State(state): State<Arc<AppState>>,
+    principal: Principal,
     Json(payload): Json<CreateOrganizationRequest>,
 ) -> Result<impl IntoResponse, AppError> {
+    // The wallet becomes the organization's owner, so only it can register
+    if payload.wallet_address != principal.account {
+        principal.require_admin()?;
+    }
+
     // Check if wallet address already exists
     let existing = sqlx::query_as::<_, Organization>(
         "SELECT * FROM organizations WHERE wallet_address = $1"
@@ -76,17 +110,22 @@ async fn create_organization(
     Ok((StatusCode::CREATED, Json(org)))
 }
 
-/// List all organizations with optional filtering
+/// List organizations, newest first, one page at a time
 async fn list_organizations(
     State(state): State<Arc<AppState>>,
+    Query(params): Query<PageParams>,
 ) -> Result<impl IntoResponse, AppError> {
-    let orgs = sqlx::query_as::<_, Organization>(
-        "SELECT * FROM organizations ORDER BY created_at DESC"
-    )
-    .fetch_all(&state.db)
-    .await?;
+    let page = ORGANIZATIONS.page(None, params.cursor.as_deref(), params.limit)?;
+
+    let mut query = QueryBuilder::<Postgres>::new("SELECT * FROM organizations WHERE TRUE");
+    page.push_keyset(&mut query);
 
-    Ok(Json(orgs))
+    let orgs = query
+        .build_query_as::<Organization>()
+        .fetch_all(&state.db)
+        .await?;
+
+    Ok(Json(page.finish(orgs)?))
 }
 
 /// Get organization by ID
@@ -124,9 +163,12 @@ async fn get_organization_by_wallet(
 /// Update organization
 async fn update_organization(
     State(state): State<Arc<AppState>>,
+    principal: Principal,
     Path(id): Path<Uuid>,
     Json(payload): Json<UpdateOrganizationRequest>,
 ) -> Result<impl IntoResponse, AppError> {
+    principal.require_org_owner_or_admin(id)?;
+
     // Get existing organization
     let mut org = sqlx::query_as::<_, Organization>(
         "SELECT * FROM organizations WHERE id = $1"
@@ -147,15 +189,35 @@ async fn update_organization(
         org.description = Some(description);
     }
     if let Some(verified) = payload.verified {
+        // Verification is the platform vouching for the organization
+        if verified != org.verified {
+            principal.require_admin()?;
+        }
         org.verified = verified;
     }
+    if let Some(threshold) = payload.approval_threshold {
+        if threshold < 1 {
+            return Err(AppError::BadRequest(
+                "Approval threshold must be at least 1".to_string(),
+            ));
+        }
+        // Owners may tighten their own quorum; loosening it is a platform call
+        if threshold < org.approval_threshold {
+            principal.require_admin()?;
+        }
+        if threshold != org.approval_threshold {
+            principal.require_step_up(unix_now(), state.config.step_up_max_age_secs)?;
+        }
+        org.approval_threshold = threshold;
+    }
 
     // Update in database
     let updated_org = sqlx::query_as::<_, Organization>(
         r#"
         UPDATE organizations
-        SET name = $1, email = $2, description = $3, verified = $4, updated_at = NOW()
-        WHERE id = $5
+        SET name = $1, email = $2, description = $3, verified = $4,
+            approval_threshold = $5, updated_at = NOW()
+        WHERE id = $6
         RETURNING *
         "#,
     )
@@ -163,6 +225,7 @@ async fn update_organization(
     .bind(&org.email)
     .bind(&org.description)
     .bind(org.verified)
+    .bind(org.approval_threshold)
     .bind(id)
     .fetch_one(&state.db)
     .await?;
@@ -177,10 +240,153 @@ async fn update_organization(
     .bind("organization")
     .bind(id)
     .bind("updated")
-    .bind(&org.wallet_address)
-    .bind(json!({"verified": org.verified}))
+    .bind(&principal.account)
+    .bind(json!({
+        "verified": org.verified,
+        "approval_threshold": org.approval_threshold
+    }))
     .execute(&state.db)
     .await;
 
     Ok(Json(updated_org))
 }
+
+/// List the accounts acting for an organization, one page at a time
+async fn list_members(
+    State(state): State<Arc<AppState>>,
+    principal: Principal,
+    Path(id): Path<Uuid>,
+    Query(params): Query<PageParams>,
+) -> Result<impl IntoResponse, AppError> {
+    if principal.require_org_member(id).is_err() {
+        principal.require_auditor()?;
+    }
+
+    let page = MEMBERS.page(None, params.cursor.as_deref(), params.limit)?;
+
+    let wallet_address = sqlx::query_scalar::<_, String>(
+        "SELECT wallet_address FROM organizations WHERE id = $1",
+    )
+    .bind(id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Organization not found".to_string()))?;
+
+    let mut query = QueryBuilder::<Postgres>::new(
+        r#"
+        SELECT * FROM (
+            SELECT id AS org_id, wallet_address AS account, 'owner' AS role, created_at
+            FROM organizations WHERE id = "#,
+    );
+    query.push_bind(id).push(
+        r#"
+            UNION ALL
+            SELECT org_id, account, role, created_at
+            FROM organization_members WHERE org_id = "#,
+    );
+    query
+        .push_bind(id)
+        .push(" AND account <> ")
+        .push_bind(wallet_address)
+        .push(") members WHERE TRUE");
+    page.push_keyset(&mut query);
+
+    let members = query
+        .build_query_as::<OrganizationMember>()
+        .fetch_all(&state.db)
+        .await?;
+
+    Ok(Json(page.finish(members)?))
+}
+
+/// Add an owner or member to an organization, or change their role
+async fn add_member(
+    State(state): State<Arc<AppState>>,
+    principal: Principal,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<AddMemberRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    principal.require_org_owner_or_admin(id)?;
+
+    if OrgRole::parse(&payload.role).is_none() {
+        return Err(AppError::BadRequest(
+            "Role must be owner or member".to_string(),
+        ));
+    }
+    if stellar_strkey::ed25519::PublicKey::from_string(&payload.account).is_err() {
+        return Err(AppError::BadRequest(format!(
+            "Invalid Stellar account {}",
+            payload.account
+        )));
+    }
+
+    let member = sqlx::query_as::<_, OrganizationMember>(
+        r#"
+        INSERT INTO organization_members (org_id, account, role)
+        SELECT id, $2, $3 FROM organizations WHERE id = $1
+        ON CONFLICT (org_id, account) DO UPDATE SET role = EXCLUDED.role
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(&payload.account)
+    .bind(&payload.role)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Organization not found".to_string()))?;
+
+    // Log audit
+    let _ = sqlx::query(
+        r#"
+        INSERT INTO audit_logs (entity_type, entity_id, action, actor_address, details)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+    )
+    .bind("organization")
+    .bind(id)
+    .bind("member_added")
+    .bind(&principal.account)
+    .bind(json!({"account": &payload.account, "role": &payload.role}))
+    .execute(&state.db)
+    .await;
+
+    Ok((StatusCode::CREATED, Json(member)))
+}
+
+/// Remove an account from an organization
+async fn remove_member(
+    State(state): State<Arc<AppState>>,
+    principal: Principal,
+    Path((id, account)): Path<(Uuid, String)>,
+) -> Result<impl IntoResponse, AppError> {
+    principal.require_org_owner_or_admin(id)?;
+
+    let removed = sqlx::query(
+        "DELETE FROM organization_members WHERE org_id = $1 AND account = $2",
+    )
+    .bind(id)
+    .bind(&account)
+    .execute(&state.db)
+    .await?;
+
+    if removed.rows_affected() == 0 {
+        return Err(AppError::NotFound("Member not found".to_string()));
+    }
+
+    // Log audit
+    let _ = sqlx::query(
+        r#"
+        INSERT INTO audit_logs (entity_type, entity_id, action, actor_address, details)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+    )
+    .bind("organization")
+    .bind(id)
+    .bind("member_removed")
+    .bind(&principal.account)
+    .bind(json!({"account": &account}))
+    .execute(&state.db)
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
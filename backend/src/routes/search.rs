@@ -0,0 +1,47 @@
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+    Json, Router,
+    routing::get,
+};
+use std::sync::Arc;
+
+use crate::{
+    error::AppError,
+    models::{SearchQueryParams, SearchResponse},
+    services::search::{self, SearchScope, MAX_QUERY_LEN},
+    AppState,
+};
+
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", get(search))
+}
+
+/// Search campaigns and organizations by keyword, ranked across both
+async fn search(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<SearchQueryParams>,
+) -> Result<impl IntoResponse, AppError> {
+    let query = params.q.trim().to_string();
+    if query.is_empty() || query.chars().count() > MAX_QUERY_LEN {
+        return Err(AppError::BadRequest(format!(
+            "Query must be between 1 and {} characters",
+            MAX_QUERY_LEN
+        )));
+    }
+    let scope = SearchScope::parse(params.kind.as_deref())?;
+    let limit = params.limit.unwrap_or(20).clamp(1, 50);
+
+    let mut results = search::full_text(&state.db, &query, scope, limit).await?;
+    let fuzzy = results.is_empty();
+    if fuzzy {
+        results = search::fuzzy(&state.db, &query, scope, limit).await?;
+    }
+
+    Ok(Json(SearchResponse {
+        query,
+        fuzzy,
+        results,
+    }))
+}
@@ -0,0 +1,429 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json, Router,
+    routing::{get, post, put},
+};
+use serde_json::json;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use super::auth::{context, signing_key};
+use crate::{
+    error::AppError,
+    models::{
+        ConfirmPasswordResetRequest, LinkOrganizationRequest, LoginRequest,
+        PasswordResetRequest, RecoveryCodesResponse, RefreshRequest, RegisterRequest,
+        TokenRequest, TokenResponse, TotpCodeRequest, TotpEnrollmentResponse, User,
+    },
+    services::{
+        accounts::{self, hash_password, normalize_email, validate_password, verify_password},
+        auth::{issue_token, unix_now, AuthUser},
+        roles::{OrgRole, Principal},
+        sep10::verify_challenge,
+        totp,
+    },
+    AppState,
+};
+
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/register", post(register))
+        .route("/login", post(login))
+        .route("/refresh", post(refresh))
+        .route("/logout", post(logout))
+        .route("/password-reset", post(request_password_reset))
+        .route("/password-reset/confirm", post(confirm_password_reset))
+        .route("/me", get(get_me))
+        .route("/me/stellar-address", put(link_stellar_address))
+        .route("/me/totp", post(start_totp_enrollment))
+        .route("/me/totp/confirm", post(confirm_totp_enrollment))
+        .route("/me/totp/disable", post(disable_totp))
+        .route("/me/step-up", post(step_up))
+        .route("/:id/organization", put(link_organization).delete(unlink_organization))
+}
+
+/// Register a staff account and start a session
+async fn register(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<RegisterRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let email = normalize_email(&payload.email)?;
+    validate_password(&payload.password)?;
+
+    let password_hash = hash_password(&payload.password, bcrypt::DEFAULT_COST).await?;
+    let user = sqlx::query_as::<_, User>(
+        r#"
+        INSERT INTO users (email, password_hash)
+        VALUES ($1, $2)
+        ON CONFLICT (email) DO NOTHING
+        RETURNING *
+        "#,
+    )
+    .bind(&email)
+    .bind(&password_hash)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| AppError::BadRequest("An account with this email already exists".to_string()))?;
+
+    let session = accounts::issue_session(&state, user).await?;
+
+    Ok((StatusCode::CREATED, Json(session)))
+}
+
+/// Sign in with email and password
+async fn login(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<LoginRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let invalid = || AppError::Unauthorized("Invalid email or password".to_string());
+    let email = normalize_email(&payload.email).map_err(|_| invalid())?;
+
+    let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE email = $1")
+        .bind(&email)
+        .fetch_optional(&state.db)
+        .await?;
+
+    let hash = user.as_ref().map_or(accounts::DUMMY_HASH, |user| user.password_hash.as_str());
+    let password_matches = verify_password(&payload.password, hash).await?;
+    let user = user.filter(|_| password_matches).ok_or_else(invalid)?;
+
+    Ok(Json(accounts::issue_session(&state, user).await?))
+}
+
+/// Exchange a refresh token for a new session
+async fn refresh(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<RefreshRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(Json(accounts::refresh_session(&state, &payload.refresh_token).await?))
+}
+
+/// Revoke a refresh token
+async fn logout(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<RefreshRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    accounts::revoke_session(&state, &payload.refresh_token).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Email a password reset token; always accepted so emails cannot be probed
+async fn request_password_reset(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<PasswordResetRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let Ok(email) = normalize_email(&payload.email) else {
+        return Ok(StatusCode::ACCEPTED);
+    };
+
+    accounts::send_password_reset(
+        &state.db,
+        state.mailer.as_ref(),
+        &email,
+        state.config.password_reset_expiry_secs,
+    )
+    .await?;
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// Set a new password with a reset token, ending every existing session
+async fn confirm_password_reset(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<ConfirmPasswordResetRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    validate_password(&payload.password)?;
+    let password_hash = hash_password(&payload.password, bcrypt::DEFAULT_COST).await?;
+
+    let user_id = sqlx::query_scalar::<_, Uuid>(
+        r#"
+        UPDATE password_reset_tokens
+        SET used_at = NOW()
+        WHERE token_hash = $1 AND used_at IS NULL AND expires_at > NOW()
+        RETURNING user_id
+        "#,
+    )
+    .bind(accounts::token_hash(&payload.token))
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| AppError::BadRequest("Invalid or expired reset token".to_string()))?;
+
+    sqlx::query("UPDATE users SET password_hash = $1, updated_at = NOW() WHERE id = $2")
+        .bind(&password_hash)
+        .bind(user_id)
+        .execute(&state.db)
+        .await?;
+    accounts::revoke_all_sessions(&state, user_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Get the signed-in staff account
+async fn get_me(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(Json(load_user(&state, staff_id(&user)?).await?))
+}
+
+/// Link a Stellar address, proven with a signed SEP-10 challenge
+async fn link_stellar_address(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Json(payload): Json<TokenRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = staff_id(&user)?;
+    let server = signing_key(&state)?;
+    let account = verify_challenge(
+        server.public_key(),
+        &payload.transaction,
+        &context(&state),
+        unix_now(),
+    )?;
+
+    let linked = sqlx::query_as::<_, User>(
+        r#"
+        UPDATE users SET stellar_address = $1, updated_at = NOW()
+        WHERE id = $2 AND NOT EXISTS (
+            SELECT 1 FROM users WHERE stellar_address = $1 AND id <> $2
+        )
+        RETURNING *
+        "#,
+    )
+    .bind(&account)
+    .bind(user_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| {
+        AppError::BadRequest("Stellar address is linked to another account".to_string())
+    })?;
+
+    // Existing tokens still carry the old identity
+    accounts::revoke_all_sessions(&state, user_id).await?;
+
+    Ok(Json(linked))
+}
+
+/// Start TOTP enrolment; the secret is inactive until a code confirms it
+async fn start_totp_enrollment(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    let user = load_user(&state, staff_id(&user)?).await?;
+    if user.totp_enabled_at.is_some() {
+        return Err(AppError::BadRequest(
+            "Two-factor authentication is already enabled".to_string(),
+        ));
+    }
+
+    let secret = totp::generate_secret();
+    sqlx::query(
+        "UPDATE users SET totp_secret = $1, totp_last_step = NULL, updated_at = NOW() WHERE id = $2",
+    )
+    .bind(&secret)
+    .bind(user.id)
+    .execute(&state.db)
+    .await?;
+
+    Ok(Json(TotpEnrollmentResponse {
+        provisioning_uri: totp::provisioning_uri(totp::ISSUER, &user.email, &secret),
+        secret,
+    }))
+}
+
+/// Confirm TOTP enrolment with a first code and receive recovery codes
+async fn confirm_totp_enrollment(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Json(payload): Json<TotpCodeRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let user = load_user(&state, staff_id(&user)?).await?;
+    if user.totp_enabled_at.is_some() {
+        return Err(AppError::BadRequest(
+            "Two-factor authentication is already enabled".to_string(),
+        ));
+    }
+    let secret = user
+        .totp_secret
+        .as_deref()
+        .ok_or_else(|| AppError::BadRequest("Start TOTP enrolment first".to_string()))?;
+    let step = totp::verify_code(secret, &payload.code, unix_now())?
+        .ok_or_else(|| AppError::Unauthorized("Invalid two-factor code".to_string()))?;
+
+    let mut tx = state.db.begin().await?;
+    sqlx::query(
+        r#"
+        UPDATE users SET totp_enabled_at = NOW(), totp_last_step = $1, updated_at = NOW()
+        WHERE id = $2
+        "#,
+    )
+    .bind(step as i64)
+    .bind(user.id)
+    .execute(&mut *tx)
+    .await?;
+    let recovery_codes = totp::replace_recovery_codes(&mut tx, user.id).await?;
+    tx.commit().await?;
+
+    tracing::info!("TOTP enabled for user {}", user.id);
+
+    Ok(Json(RecoveryCodesResponse { recovery_codes }))
+}
+
+/// Turn TOTP off with a current or recovery code
+async fn disable_totp(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Json(payload): Json<TotpCodeRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let user = load_user(&state, staff_id(&user)?).await?;
+    totp::check_second_factor(&state.db, &user, &payload.code, unix_now()).await?;
+
+    let mut tx = state.db.begin().await?;
+    sqlx::query(
+        r#"
+        UPDATE users
+        SET totp_secret = NULL, totp_enabled_at = NULL, totp_last_step = NULL, updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(user.id)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query("DELETE FROM totp_recovery_codes WHERE user_id = $1")
+        .bind(user.id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    tracing::info!("TOTP disabled for user {}", user.id);
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Exchange a current or recovery code for a token that passes step-up checks
+async fn step_up(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Json(payload): Json<TotpCodeRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let user = load_user(&state, staff_id(&user)?).await?;
+    let now = unix_now();
+    totp::check_second_factor(&state.db, &user, &payload.code, now).await?;
+
+    let account = accounts::subject(&user);
+    let (token, claims) = issue_token(&state.config, &account, Some(user.id), Some(now), now)?;
+
+    Ok(Json(TokenResponse {
+        token,
+        account,
+        expires_at: claims.exp,
+    }))
+}
+
+/// Attach a staff account to an organization
+async fn link_organization(
+    State(state): State<Arc<AppState>>,
+    principal: Principal,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<LinkOrganizationRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    principal.require_org_owner_or_admin(payload.org_id)?;
+    if OrgRole::parse(&payload.role).is_none() {
+        return Err(AppError::BadRequest(
+            "Role must be owner or member".to_string(),
+        ));
+    }
+
+    // Moving someone out of another organization needs that one's consent too
+    let user = load_user(&state, id).await?;
+    if let Some(current) = user.org_id.filter(|current| *current != payload.org_id) {
+        principal.require_org_owner_or_admin(current)?;
+    }
+
+    let linked = sqlx::query_as::<_, User>(
+        r#"
+        UPDATE users SET org_id = $1, org_role = $2, updated_at = NOW()
+        WHERE id = $3
+        RETURNING *
+        "#,
+    )
+    .bind(payload.org_id)
+    .bind(&payload.role)
+    .bind(id)
+    .fetch_one(&state.db)
+    .await?;
+
+    // Log audit
+    let _ = sqlx::query(
+        r#"
+        INSERT INTO audit_logs (entity_type, entity_id, action, actor_address, details)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+    )
+    .bind("organization")
+    .bind(payload.org_id)
+    .bind("member_added")
+    .bind(&principal.account)
+    .bind(json!({"user_id": id, "role": &payload.role}))
+    .execute(&state.db)
+    .await;
+
+    Ok(Json(linked))
+}
+
+/// Detach a staff account from its organization
+async fn unlink_organization(
+    State(state): State<Arc<AppState>>,
+    principal: Principal,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let user = load_user(&state, id).await?;
+    let org_id = user
+        .org_id
+        .ok_or_else(|| AppError::NotFound("User is not linked to an organization".to_string()))?;
+    principal.require_org_owner_or_admin(org_id)?;
+
+    let unlinked = sqlx::query_as::<_, User>(
+        r#"
+        UPDATE users SET org_id = NULL, org_role = NULL, updated_at = NOW()
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .fetch_one(&state.db)
+    .await?;
+
+    // Log audit
+    let _ = sqlx::query(
+        r#"
+        INSERT INTO audit_logs (entity_type, entity_id, action, actor_address, details)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+    )
+    .bind("organization")
+    .bind(org_id)
+    .bind("member_removed")
+    .bind(&principal.account)
+    .bind(json!({"user_id": id}))
+    .execute(&state.db)
+    .await;
+
+    Ok(Json(unlinked))
+}
+
+fn staff_id(user: &AuthUser) -> Result<Uuid, AppError> {
+    user.user_id
+        .ok_or_else(|| AppError::Unauthorized("Staff account token required".to_string()))
+}
+
+async fn load_user(state: &AppState, id: Uuid) -> Result<User, AppError> {
+    sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))
+}
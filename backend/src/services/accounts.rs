@@ -0,0 +1,312 @@
+use chrono::{Duration, Utc};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    error::AppError,
+    models::{SessionResponse, User},
+    services::{
+        auth::{issue_token, unix_now},
+        mailer::{password_reset_email, Mailer},
+    },
+    AppState,
+};
+
+pub const MIN_PASSWORD_LEN: usize = 8;
+/// bcrypt only looks at the first 72 bytes
+pub const MAX_PASSWORD_LEN: usize = 72;
+/// Checked against when an email is unknown so failed logins take as long
+/// whether or not the account exists
+pub const DUMMY_HASH: &str = "$2b$12$C6UzMDM.H6dfI/f/IKcEeO9Q8kPVX7GJ9YJtZxVZbQyHhS2pJMnS2";
+
+/// Hash a password with bcrypt off the async runtime
+pub async fn hash_password(password: &str, cost: u32) -> Result<String, AppError> {
+    let password = password.to_string();
+    tokio::task::spawn_blocking(move || bcrypt::hash(password, cost))
+        .await
+        .map_err(|e| AppError::Internal(format!("Password hashing failed: {}", e)))?
+        .map_err(|e| AppError::Internal(format!("Password hashing failed: {}", e)))
+}
+
+pub async fn verify_password(password: &str, hash: &str) -> Result<bool, AppError> {
+    let password = password.to_string();
+    let hash = hash.to_string();
+    tokio::task::spawn_blocking(move || bcrypt::verify(password, &hash).unwrap_or(false))
+        .await
+        .map_err(|e| AppError::Internal(format!("Password check failed: {}", e)))
+}
+
+pub fn validate_password(password: &str) -> Result<(), AppError> {
+    if password.len() < MIN_PASSWORD_LEN || password.len() > MAX_PASSWORD_LEN {
+        return Err(AppError::BadRequest(format!(
+            "Password must be between {} and {} bytes",
+            MIN_PASSWORD_LEN, MAX_PASSWORD_LEN
+        )));
+    }
+    Ok(())
+}
+
+/// Trim and lowercase an email address, rejecting obviously invalid ones
+pub fn normalize_email(email: &str) -> Result<String, AppError> {
+    let email = email.trim().to_lowercase();
+    let valid = match email.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty() && domain.contains('.') && !domain.starts_with('.') && !domain.ends_with('.')
+        }
+        None => false,
+    };
+    if !valid || email.len() > 255 || email.contains(char::is_whitespace) {
+        return Err(AppError::BadRequest("Invalid email address".to_string()));
+    }
+    Ok(email)
+}
+
+/// Random opaque token and the digest stored in its place
+pub fn new_token() -> (String, String) {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    let token: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    let hash = token_hash(&token);
+    (token, hash)
+}
+
+pub fn token_hash(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Identity a staff account acts as: its linked Stellar address if any
+pub fn subject(user: &User) -> String {
+    user.stellar_address
+        .clone()
+        .unwrap_or_else(|| format!("user:{}", user.id))
+}
+
+/// Staff account with TOTP enabled that is linked to a Stellar address.
+///
+/// Wallet logins for such an address act as that account, so step-up
+/// checks apply to them as they do to password logins.
+pub async fn totp_user_for_address(db: &PgPool, address: &str) -> Result<Option<Uuid>, AppError> {
+    Ok(sqlx::query_scalar::<_, Uuid>(
+        "SELECT id FROM users WHERE stellar_address = $1 AND totp_enabled_at IS NOT NULL",
+    )
+    .bind(address)
+    .fetch_optional(db)
+    .await?)
+}
+
+/// Start a session: a short-lived access token and a stored refresh token
+pub async fn issue_session(state: &AppState, user: User) -> Result<SessionResponse, AppError> {
+    let (refresh_token, refresh_hash) = new_token();
+    sqlx::query(
+        "INSERT INTO refresh_tokens (user_id, token_hash, expires_at) VALUES ($1, $2, $3)",
+    )
+    .bind(user.id)
+    .bind(&refresh_hash)
+    .bind(Utc::now() + Duration::seconds(state.config.refresh_token_expiry_secs as i64))
+    .execute(&state.db)
+    .await?;
+
+    let (access_token, claims) =
+        issue_token(&state.config, &subject(&user), Some(user.id), None, unix_now())?;
+
+    Ok(SessionResponse {
+        access_token,
+        refresh_token,
+        expires_at: claims.exp,
+        user,
+    })
+}
+
+/// Exchange a refresh token for a new session, revoking the old token.
+///
+/// Presenting a token that was already revoked means it leaked or was
+/// replayed, so every session of that user is revoked.
+pub async fn refresh_session(state: &AppState, refresh_token: &str) -> Result<SessionResponse, AppError> {
+    let invalid = || AppError::Unauthorized("Invalid or expired refresh token".to_string());
+    let mut tx = state.db.begin().await?;
+
+    let (token_id, user_id, expired, revoked) = sqlx::query_as::<_, (Uuid, Uuid, bool, bool)>(
+        r#"
+        SELECT id, user_id, expires_at <= NOW(), revoked_at IS NOT NULL
+        FROM refresh_tokens WHERE token_hash = $1
+        FOR UPDATE
+        "#,
+    )
+    .bind(token_hash(refresh_token))
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(invalid)?;
+
+    if revoked {
+        drop(tx);
+        revoke_all_sessions(state, user_id).await?;
+        tracing::warn!("Revoked refresh token reused for user {}", user_id);
+        return Err(invalid());
+    }
+    if expired {
+        return Err(invalid());
+    }
+
+    sqlx::query("UPDATE refresh_tokens SET revoked_at = NOW() WHERE id = $1")
+        .bind(token_id)
+        .execute(&mut *tx)
+        .await?;
+    let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_one(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    issue_session(state, user).await
+}
+
+/// Revoke one refresh token; unknown tokens are ignored
+pub async fn revoke_session(state: &AppState, refresh_token: &str) -> Result<(), AppError> {
+    sqlx::query(
+        "UPDATE refresh_tokens SET revoked_at = NOW() WHERE token_hash = $1 AND revoked_at IS NULL",
+    )
+    .bind(token_hash(refresh_token))
+    .execute(&state.db)
+    .await?;
+    Ok(())
+}
+
+pub async fn revoke_all_sessions(state: &AppState, user_id: Uuid) -> Result<(), AppError> {
+    sqlx::query(
+        "UPDATE refresh_tokens SET revoked_at = NOW() WHERE user_id = $1 AND revoked_at IS NULL",
+    )
+    .bind(user_id)
+    .execute(&state.db)
+    .await?;
+    Ok(())
+}
+
+/// Email a password reset token to `email` if it belongs to an account.
+///
+/// Unknown addresses and delivery failures are not reported, so callers
+/// cannot use resets to probe which emails are registered.
+pub async fn send_password_reset(
+    db: &PgPool,
+    mailer: &dyn Mailer,
+    email: &str,
+    expiry_secs: u64,
+) -> Result<(), AppError> {
+    let user_id = sqlx::query_scalar::<_, Uuid>("SELECT id FROM users WHERE email = $1")
+        .bind(email)
+        .fetch_optional(db)
+        .await?;
+
+    if let Some(user_id) = user_id {
+        let (token, token_hash) = new_token();
+        sqlx::query(
+            "INSERT INTO password_reset_tokens (user_id, token_hash, expires_at) VALUES ($1, $2, $3)",
+        )
+        .bind(user_id)
+        .bind(&token_hash)
+        .bind(Utc::now() + Duration::seconds(expiry_secs as i64))
+        .execute(db)
+        .await?;
+
+        let (subject, text) = password_reset_email(&token, expiry_secs);
+        if let Err(e) = mailer.send(email, &subject, &text).await {
+            tracing::warn!("Failed to send password reset email to user {}: {}", user_id, e);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::mailer::RecordingMailer;
+
+    #[tokio::test]
+    async fn passwords_round_trip_through_bcrypt() {
+        let hash = hash_password("correct horse", 4).await.unwrap();
+        assert!(hash.starts_with("$2"));
+        assert!(verify_password("correct horse", &hash).await.unwrap());
+        assert!(!verify_password("wrong horse", &hash).await.unwrap());
+        assert!(!verify_password("correct horse", "not a hash").await.unwrap());
+        assert_eq!(bcrypt::verify("correct horse", DUMMY_HASH).ok(), Some(false));
+    }
+
+    #[test]
+    fn tokens_are_stored_as_digests() {
+        let (token, hash) = new_token();
+        assert_eq!(token.len(), 64);
+        assert_eq!(hash, token_hash(&token));
+        assert_ne!(hash, token);
+        assert_ne!(new_token().0, token);
+    }
+
+    #[test]
+    fn emails_and_passwords_are_validated() {
+        assert_eq!(normalize_email(" Staff@NGO.org ").unwrap(), "staff@ngo.org");
+        assert!(normalize_email("staff").is_err());
+        assert!(normalize_email("@ngo.org").is_err());
+        assert!(normalize_email("staff@ngo").is_err());
+
+        assert!(validate_password("short").is_err());
+        assert!(validate_password("long enough").is_ok());
+        assert!(validate_password(&"x".repeat(73)).is_err());
+    }
+
+    #[sqlx::test]
+    async fn reset_tokens_are_mailed_not_logged(db: PgPool) {
+        let user_id = sqlx::query_scalar::<_, Uuid>(
+            "INSERT INTO users (email, password_hash) VALUES ('staff@ngo.org', $1) RETURNING id",
+        )
+        .bind(DUMMY_HASH)
+        .fetch_one(&db)
+        .await
+        .unwrap();
+        let mailer = RecordingMailer::default();
+
+        send_password_reset(&db, &mailer, "staff@ngo.org", 3600).await.unwrap();
+        send_password_reset(&db, &mailer, "nobody@ngo.org", 3600).await.unwrap();
+
+        let sent = mailer.sent.lock().unwrap().clone();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].0, "staff@ngo.org");
+        let token = sent[0].2.lines().nth(2).unwrap();
+        let stored = sqlx::query_scalar::<_, Uuid>(
+            "SELECT user_id FROM password_reset_tokens WHERE token_hash = $1",
+        )
+        .bind(token_hash(token))
+        .fetch_one(&db)
+        .await
+        .unwrap();
+        assert_eq!(stored, user_id);
+    }
+
+    #[sqlx::test]
+    async fn wallets_resolve_to_staff_accounts_with_totp(db: PgPool) {
+        let user_id = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            INSERT INTO users (email, password_hash, stellar_address)
+            VALUES ('staff@ngo.org', $1, 'GSTAFF') RETURNING id
+            "#,
+        )
+        .bind(DUMMY_HASH)
+        .fetch_one(&db)
+        .await
+        .unwrap();
+
+        assert_eq!(totp_user_for_address(&db, "GSTAFF").await.unwrap(), None);
+
+        sqlx::query("UPDATE users SET totp_secret = 'secret', totp_enabled_at = NOW() WHERE id = $1")
+            .bind(user_id)
+            .execute(&db)
+            .await
+            .unwrap();
+
+        assert_eq!(totp_user_for_address(&db, "GSTAFF").await.unwrap(), Some(user_id));
+        assert_eq!(totp_user_for_address(&db, "GDONOR").await.unwrap(), None);
+    }
+}
@@ -0,0 +1,107 @@
+use data_encoding::BASE64;
+use ed25519_dalek::{Signature, VerifyingKey};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::error::AppError;
+
+/// SEP-53 prefix wallets add before hashing and signing an arbitrary message
+pub const SIGNED_MESSAGE_PREFIX: &str = "Stellar Signed Message:\n";
+
+/// The text an approver signs: everything that decides where the money goes
+pub fn approval_payload(disbursement_id: Uuid, recipient: &str, amount: i64, nonce: &str) -> String {
+    format!(
+        "AidFlow disbursement approval\ndisbursement: {}\nrecipient: {}\namount: {}\nnonce: {}",
+        disbursement_id, recipient, amount, nonce
+    )
+}
+
+/// Check that `signature` (base64) is `approver`'s SEP-53 signature of `payload`
+pub fn verify_approval(approver: &str, payload: &str, signature: &str) -> Result<(), AppError> {
+    let key = stellar_strkey::ed25519::PublicKey::from_string(approver).map_err(|_| {
+        AppError::BadRequest(
+            "Approving requires a Stellar account; link one to your staff account first"
+                .to_string(),
+        )
+    })?;
+    let verifying_key = VerifyingKey::from_bytes(&key.0)
+        .map_err(|_| AppError::BadRequest("Approver key is not a valid ed25519 key".to_string()))?;
+
+    let signature = BASE64
+        .decode(signature.trim().as_bytes())
+        .ok()
+        .and_then(|bytes| Signature::from_slice(&bytes).ok())
+        .ok_or_else(|| {
+            AppError::BadRequest("Signature must be a base64 ed25519 signature".to_string())
+        })?;
+
+    verifying_key
+        .verify_strict(&message_hash(payload), &signature)
+        .map_err(|_| {
+            AppError::Verification("Signature does not match the approval payload".to_string())
+        })
+}
+
+fn message_hash(payload: &str) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(SIGNED_MESSAGE_PREFIX.as_bytes());
+    hasher.update(payload.as_bytes());
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    fn account(key: &SigningKey) -> String {
+        stellar_strkey::ed25519::PublicKey(key.verifying_key().to_bytes()).to_string()
+    }
+
+    fn sign(key: &SigningKey, payload: &str) -> String {
+        BASE64.encode(&key.sign(&message_hash(payload)).to_bytes())
+    }
+
+    #[test]
+    fn signatures_match_the_sep_53_vector() {
+        let seed = stellar_strkey::ed25519::PrivateKey::from_string(
+            "SAKICEVQLYWGSOJS4WW7HZJWAHZVEEBS527LHK5V4MLJALYKICQCJXMW",
+        )
+        .unwrap();
+        let key = SigningKey::from_bytes(&seed.0);
+
+        let signature = "fO5dbYhXUhBMhe6kId/cuVq/AfEnHRHEvsP8vXh03M1uLpi5e46yO2Q8rEBzu3feXQewcQE5GArp88u6ePK6BA==";
+        assert_eq!(sign(&key, "Hello, World!"), signature);
+        assert!(verify_approval(&account(&key), "Hello, World!", signature).is_ok());
+    }
+
+    #[test]
+    fn approvals_are_bound_to_the_payload_and_the_approver() {
+        let approver = SigningKey::from_bytes(&[7u8; 32]);
+        let other = SigningKey::from_bytes(&[9u8; 32]);
+        let id = Uuid::nil();
+        let recipient = account(&other);
+        let payload = approval_payload(id, &recipient, 500, "abc123");
+        let signature = sign(&approver, &payload);
+
+        assert!(verify_approval(&account(&approver), &payload, &signature).is_ok());
+
+        let inflated = approval_payload(id, &recipient, 5000, "abc123");
+        assert!(matches!(
+            verify_approval(&account(&approver), &inflated, &signature),
+            Err(AppError::Verification(_))
+        ));
+        assert!(matches!(
+            verify_approval(&account(&other), &payload, &signature),
+            Err(AppError::Verification(_))
+        ));
+        assert!(matches!(
+            verify_approval("user:42", &payload, &signature),
+            Err(AppError::BadRequest(_))
+        ));
+        assert!(matches!(
+            verify_approval(&account(&approver), &payload, "not base64"),
+            Err(AppError::BadRequest(_))
+        ));
+    }
+}
@@ -0,0 +1,291 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    config::Config,
+    error::AppError,
+    services::{mock_chain::MockChain, stellar_service::RpcChain},
+};
+
+/// Contract invocation found in a transaction, with arguments as JSON
+/// (addresses as strkeys, 128-bit integers as strings)
+#[derive(Debug, Clone)]
+pub struct ContractCall {
+    pub contract_id: String,
+    pub function: String,
+    pub args: Vec<serde_json::Value>,
+}
+
+/// Transaction as seen on-chain
+#[derive(Debug, Clone)]
+pub struct ChainTransaction {
+    pub successful: bool,
+    /// `None` when the transaction is not a contract invocation
+    pub call: Option<ContractCall>,
+}
+
+/// Contract call that has been checked and signed but not yet sent
+#[derive(Debug, Clone)]
+pub struct PreparedTransaction {
+    /// Hash the transaction will be known by once submitted
+    pub tx_hash: String,
+    /// Backend-specific payload, such as the signed envelope XDR
+    pub payload: String,
+}
+
+/// Operations the API needs from the donation contract
+#[async_trait]
+pub trait ChainBackend: Send + Sync {
+    /// Address (`C...`) of the donation contract this backend talks to
+    fn contract_id(&self) -> String;
+
+    /// Whether contract calls that `address` must authorize can be signed by
+    /// this backend
+    fn can_sign_for(&self, address: &str) -> bool;
+
+    /// Create a campaign and return its contract campaign id
+    async fn create_campaign(
+        &self,
+        org_wallet: &str,
+        name: &str,
+        goal_amount: i64,
+        deadline: u64,
+    ) -> Result<i32, AppError>;
+
+    /// Record a donation and return the transaction hash
+    async fn donate(
+        &self,
+        campaign_id: u32,
+        donor_address: &str,
+        amount: i64,
+    ) -> Result<String, AppError>;
+
+    /// Check and sign a disbursement without sending it. An error here means
+    /// nothing was submitted.
+    async fn prepare_disbursement(
+        &self,
+        campaign_id: u32,
+        recipient_address: &str,
+        amount: i64,
+        purpose: &str,
+        evidence_hashes: &[String],
+    ) -> Result<PreparedTransaction, AppError>;
+
+    /// Send a prepared transaction and wait for it to succeed. An error does
+    /// not mean the transaction failed: it may still have been applied.
+    async fn submit(&self, prepared: &PreparedTransaction) -> Result<(), AppError>;
+
+    /// Release funds to a recipient and return the transaction hash
+    async fn disburse(
+        &self,
+        campaign_id: u32,
+        recipient_address: &str,
+        amount: i64,
+        purpose: &str,
+        evidence_hashes: &[String],
+    ) -> Result<String, AppError> {
+        let prepared = self
+            .prepare_disbursement(campaign_id, recipient_address, amount, purpose, evidence_hashes)
+            .await?;
+        self.submit(&prepared).await?;
+        Ok(prepared.tx_hash)
+    }
+
+    async fn close_campaign(&self, campaign_id: u32) -> Result<(), AppError>;
+
+    /// Campaign as stored by the contract, with 128-bit amounts as strings
+    async fn get_campaign(&self, campaign_id: u32) -> Result<serde_json::Value, AppError>;
+
+    /// Donations recorded by the contract, oldest first, as `{donor, amount, timestamp}`
+    async fn get_donations(&self, campaign_id: u32) -> Result<Vec<serde_json::Value>, AppError>;
+
+    /// Look up a submitted transaction, `None` if the network does not know it
+    async fn get_transaction(&self, tx_hash: &str) -> Result<Option<ChainTransaction>, AppError>;
+}
+
+/// Confirm that `tx_hash` is a successful `donate` call on our contract for
+/// exactly this campaign, donor and amount.
+pub async fn verify_donation(
+    chain: &dyn ChainBackend,
+    tx_hash: &str,
+    campaign_id: u32,
+    donor_address: &str,
+    amount: i64,
+) -> Result<(), AppError> {
+    let tx = chain
+        .get_transaction(tx_hash)
+        .await?
+        .ok_or_else(|| AppError::Verification(format!("Transaction {} not found", tx_hash)))?;
+
+    if !tx.successful {
+        return Err(AppError::Verification(format!(
+            "Transaction {} did not succeed",
+            tx_hash
+        )));
+    }
+
+    let call = tx
+        .call
+        .filter(|call| call.contract_id == chain.contract_id())
+        .ok_or_else(|| {
+            AppError::Verification(format!(
+                "Transaction {} does not invoke the donation contract",
+                tx_hash
+            ))
+        })?;
+
+    if call.function != "donate" && call.function != "donate_with_receipt" {
+        return Err(AppError::Verification(format!(
+            "Transaction {} calls {} instead of donate",
+            tx_hash, call.function
+        )));
+    }
+
+    let (on_chain_campaign, on_chain_donor, on_chain_amount) = match call.args.as_slice() {
+        [campaign, donor, amount] => (
+            campaign.as_u64(),
+            donor.as_str().unwrap_or_default(),
+            amount.as_str().and_then(|a| a.parse::<i128>().ok()),
+        ),
+        _ => {
+            return Err(AppError::Verification(format!(
+                "Transaction {} has unexpected donate arguments",
+                tx_hash
+            )))
+        }
+    };
+
+    if on_chain_campaign != Some(campaign_id as u64) {
+        return Err(AppError::Verification(format!(
+            "Transaction {} donates to campaign {} instead of {}",
+            tx_hash, call.args[0], campaign_id
+        )));
+    }
+    if on_chain_donor != donor_address {
+        return Err(AppError::Verification(format!(
+            "Transaction {} was sent by {} instead of {}",
+            tx_hash, on_chain_donor, donor_address
+        )));
+    }
+    if on_chain_amount != Some(amount as i128) {
+        return Err(AppError::Verification(format!(
+            "Transaction {} donates {} instead of {}",
+            tx_hash, call.args[2], amount
+        )));
+    }
+
+    Ok(())
+}
+
+/// Reject contract calls the organization's wallet has to authorize when the
+/// backend cannot sign for that wallet
+pub fn require_signer(chain: &dyn ChainBackend, org_wallet: &str) -> Result<(), AppError> {
+    if chain.can_sign_for(org_wallet) {
+        return Ok(());
+    }
+    Err(AppError::BadRequest(format!(
+        "The API can only sign contract calls for the server's own wallet; {} must submit this call itself",
+        org_wallet
+    )))
+}
+
+/// Build the backend named by `CHAIN_BACKEND`
+pub fn from_config(config: &Config) -> Result<Arc<dyn ChainBackend>, AppError> {
+    match config.chain_backend.as_str() {
+        "rpc" => Ok(Arc::new(RpcChain::new(config.clone()))),
+        "mock" => Ok(Arc::new(MockChain::new())),
+        other => Err(AppError::Internal(format!("Unknown CHAIN_BACKEND {}", other))),
+    }
+}
+
+/// Decode a hex-encoded SHA-256 digest
+pub fn decode_hash32(hex: &str) -> Result<[u8; 32], AppError> {
+    let invalid = || AppError::BadRequest(format!("Invalid evidence hash {}", hex));
+    if hex.len() != 64 || !hex.is_ascii() {
+        return Err(invalid());
+    }
+    let mut bytes = [0u8; 32];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).map_err(|_| invalid())?;
+    }
+    Ok(bytes)
+}
+
+/// Validate a transaction hash and lowercase it, so the same transaction is
+/// never stored twice under different hex cases
+pub fn normalize_tx_hash(tx_hash: &str) -> Result<String, AppError> {
+    if tx_hash.len() != 64 || !tx_hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(AppError::BadRequest(
+            "Transaction hash must be 64 hex characters".to_string(),
+        ));
+    }
+    Ok(tx_hash.to_ascii_lowercase())
+}
+
+/// Transaction hash for backends that do not submit real transactions
+pub(crate) fn sequential_tx_hash(n: u64) -> String {
+    format!("{:064x}", n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DONOR: &str = "GBRPYHIL2CI3FNQ4BXLFMNDLFJUNPU2HY3ZMFSHONUCEOASW7QC7OX2H";
+    const OTHER: &str = "GAAZI4TCR3TY5OJHCTJC2A4QSY6CJWJH5IAJTGKIN2ER7LBNVKOCCWN7";
+
+    async fn donated(chain: &MockChain) -> String {
+        let id = chain.create_campaign(OTHER, "Water", 1_000, 2_000_000_000).await.unwrap();
+        chain.donate(id as u32, DONOR, 250).await.unwrap()
+    }
+
+    fn verification_message(result: Result<(), AppError>) -> String {
+        match result {
+            Err(AppError::Verification(msg)) => msg,
+            other => panic!("expected a verification error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn verify_donation_accepts_matching_transaction() {
+        let chain = MockChain::new();
+        let tx_hash = donated(&chain).await;
+
+        verify_donation(&chain, &tx_hash, 1, DONOR, 250).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn verify_donation_rejects_mismatches() {
+        let chain = MockChain::new();
+        let tx_hash = donated(&chain).await;
+
+        let msg = verification_message(verify_donation(&chain, &tx_hash, 2, DONOR, 250).await);
+        assert!(msg.contains("campaign 1 instead of 2"));
+
+        let msg = verification_message(verify_donation(&chain, &tx_hash, 1, OTHER, 250).await);
+        assert!(msg.contains("instead of"));
+
+        let msg = verification_message(verify_donation(&chain, &tx_hash, 1, DONOR, 251).await);
+        assert!(msg.contains("donates \"250\" instead of 251"));
+
+        let msg = verification_message(
+            verify_donation(&chain, &sequential_tx_hash(1), 1, DONOR, 250).await,
+        );
+        assert!(msg.contains("calls create_campaign instead of donate"));
+
+        let msg = verification_message(
+            verify_donation(&chain, &sequential_tx_hash(99), 1, DONOR, 250).await,
+        );
+        assert!(msg.contains("not found"));
+    }
+
+    #[test]
+    fn tx_hashes_are_validated_and_lowercased() {
+        let upper = "AB".repeat(32);
+        assert_eq!(normalize_tx_hash(&upper).unwrap(), "ab".repeat(32));
+        assert!(normalize_tx_hash("abc123").is_err());
+        assert!(normalize_tx_hash(&"zz".repeat(32)).is_err());
+        assert!(normalize_tx_hash(&format!("{} ", "ab".repeat(32))).is_err());
+    }
+}
@@ -0,0 +1,461 @@
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc, Mutex,
+    },
+    thread,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use async_trait::async_trait;
+use donation_contract::{DonationContract, DonationContractClient};
+use serde_json::json;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, BytesN, Env, InvokeError, String as SorobanString, Vec as SorobanVec,
+};
+use tokio::sync::oneshot;
+
+use crate::{
+    error::AppError,
+    services::chain::{
+        decode_hash32, sequential_tx_hash, ChainBackend, ChainTransaction, ContractCall,
+        PreparedTransaction,
+    },
+};
+
+type Job = Box<dyn FnOnce(&DonationContractClient<'_>) + Send>;
+
+/// Runs the donation contract inside a local Soroban host.
+///
+/// The host is not thread-safe, so it lives on a dedicated thread and calls
+/// are sent to it as jobs. Authorization is mocked: every call is treated as
+/// signed by the addresses it names, so this backend only exists in tests,
+/// where it checks that [`MockChain`](super::mock_chain::MockChain) follows
+/// the contract.
+pub struct InProcessChain {
+    jobs: mpsc::Sender<Job>,
+    contract_id: String,
+    transactions: Mutex<HashMap<String, ChainTransaction>>,
+    pending: Mutex<HashMap<String, PendingDisbursement>>,
+    tx_count: AtomicU64,
+}
+
+/// Disbursement prepared but not yet run against the contract
+struct PendingDisbursement {
+    campaign_id: u32,
+    recipient: String,
+    amount: i128,
+    purpose: String,
+    evidence: Vec<[u8; 32]>,
+    args: Vec<serde_json::Value>,
+}
+
+impl InProcessChain {
+    pub fn new() -> Self {
+        let (jobs, receiver) = mpsc::channel::<Job>();
+        let (registered, contract_id) = mpsc::channel();
+
+        thread::Builder::new()
+            .name("in-process-chain".to_string())
+            .spawn(move || {
+                let env = Env::default();
+                env.mock_all_auths();
+                let contract_id = env.register(DonationContract, ());
+                let client = DonationContractClient::new(&env, &contract_id);
+                client.initialize(&Address::generate(&env));
+                let _ = registered.send(contract_id.to_string().to_string());
+
+                for job in receiver {
+                    let now = SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .map(|d| d.as_secs())
+                        .unwrap_or_default();
+                    env.ledger().set_timestamp(now);
+                    job(&client);
+                }
+            })
+            .expect("Failed to start in-process chain");
+
+        Self {
+            jobs,
+            contract_id: contract_id
+                .recv()
+                .expect("In-process chain failed to register the contract"),
+            transactions: Mutex::new(HashMap::new()),
+            pending: Mutex::new(HashMap::new()),
+            tx_count: AtomicU64::new(0),
+        }
+    }
+
+    async fn run<T, F>(&self, call: F) -> Result<T, AppError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&DonationContractClient<'_>) -> Result<T, AppError> + Send + 'static,
+    {
+        let stopped = || AppError::Internal("In-process chain has stopped".to_string());
+        let (reply, result) = oneshot::channel();
+        self.jobs
+            .send(Box::new(move |client| {
+                let _ = reply.send(call(client));
+            }))
+            .map_err(|_| stopped())?;
+
+        result.await.map_err(|_| stopped())?
+    }
+
+    /// Have a fresh auditor open a dispute against the campaign
+    pub async fn open_dispute(&self, campaign_id: u32) -> Result<(), AppError> {
+        self.run(move |client| {
+            let auditor = Address::generate(&client.env);
+            client
+                .try_add_auditor(&auditor)
+                .map_err(contract_error)?
+                .map_err(conversion_error)?;
+            client
+                .try_open_dispute(&campaign_id, &auditor, &BytesN::from_array(&client.env, &[0u8; 32]))
+                .map_err(contract_error)?
+                .map_err(conversion_error)
+        })
+        .await
+    }
+
+    fn next_tx_hash(&self) -> String {
+        sequential_tx_hash(self.tx_count.fetch_add(1, Ordering::SeqCst) + 1)
+    }
+
+    /// Record a successful call and return its transaction hash
+    fn record(&self, function: &str, args: Vec<serde_json::Value>) -> String {
+        let hash = self.next_tx_hash();
+        self.record_as(&hash, function, args);
+        hash
+    }
+
+    fn record_as(&self, hash: &str, function: &str, args: Vec<serde_json::Value>) {
+        self.transactions.lock().unwrap().insert(
+            hash.to_string(),
+            ChainTransaction {
+                successful: true,
+                call: Some(ContractCall {
+                    contract_id: self.contract_id.clone(),
+                    function: function.to_string(),
+                    args,
+                }),
+            },
+        );
+    }
+}
+
+impl Default for InProcessChain {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn validate_address(address: &str) -> Result<String, AppError> {
+    stellar_strkey::Strkey::from_string(address)
+        .map(|_| address.to_string())
+        .map_err(|_| AppError::BadRequest(format!("Invalid Stellar address {}", address)))
+}
+
+fn contract_error<E: Debug>(error: Result<E, InvokeError>) -> AppError {
+    match error {
+        Ok(e) => AppError::Stellar(format!("Contract error: {:?}", e)),
+        Err(e) => AppError::Stellar(format!("Contract call failed: {:?}", e)),
+    }
+}
+
+fn conversion_error<E: Debug>(error: E) -> AppError {
+    AppError::Stellar(format!("Unexpected contract result: {:?}", error))
+}
+
+#[async_trait]
+impl ChainBackend for InProcessChain {
+    fn contract_id(&self) -> String {
+        self.contract_id.clone()
+    }
+
+    /// Authorization is mocked for every address
+    fn can_sign_for(&self, _address: &str) -> bool {
+        true
+    }
+
+    async fn create_campaign(
+        &self,
+        org_wallet: &str,
+        name: &str,
+        goal_amount: i64,
+        deadline: u64,
+    ) -> Result<i32, AppError> {
+        let org = validate_address(org_wallet)?;
+        let owned_name = name.to_string();
+
+        let id = self
+            .run(move |client| {
+                client
+                    .try_create_campaign(
+                        &Address::from_str(&client.env, &org),
+                        &SorobanString::from_str(&client.env, &owned_name),
+                        &(goal_amount as i128),
+                        &deadline,
+                    )
+                    .map_err(contract_error)?
+                    .map_err(conversion_error)
+            })
+            .await?;
+        self.record(
+            "create_campaign",
+            vec![
+                json!(org_wallet),
+                json!(name),
+                json!(goal_amount.to_string()),
+                json!(deadline),
+            ],
+        );
+
+        Ok(id as i32)
+    }
+
+    async fn donate(
+        &self,
+        campaign_id: u32,
+        donor_address: &str,
+        amount: i64,
+    ) -> Result<String, AppError> {
+        let donor = validate_address(donor_address)?;
+
+        self.run(move |client| {
+            client
+                .try_donate(
+                    &campaign_id,
+                    &Address::from_str(&client.env, &donor),
+                    &(amount as i128),
+                )
+                .map_err(contract_error)?
+                .map_err(conversion_error)
+        })
+        .await?;
+
+        Ok(self.record(
+            "donate",
+            vec![
+                json!(campaign_id),
+                json!(donor_address),
+                json!(amount.to_string()),
+            ],
+        ))
+    }
+
+    async fn prepare_disbursement(
+        &self,
+        campaign_id: u32,
+        recipient_address: &str,
+        amount: i64,
+        purpose: &str,
+        evidence_hashes: &[String],
+    ) -> Result<PreparedTransaction, AppError> {
+        let recipient = validate_address(recipient_address)?;
+        let evidence = evidence_hashes
+            .iter()
+            .map(|hash| decode_hash32(hash))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let tx_hash = self.next_tx_hash();
+        self.pending.lock().unwrap().insert(
+            tx_hash.clone(),
+            PendingDisbursement {
+                campaign_id,
+                recipient,
+                amount: amount as i128,
+                purpose: purpose.to_string(),
+                evidence,
+                args: vec![
+                    json!(campaign_id),
+                    json!(recipient_address),
+                    json!(amount.to_string()),
+                    json!(purpose),
+                    json!(evidence_hashes),
+                ],
+            },
+        );
+
+        Ok(PreparedTransaction {
+            tx_hash,
+            payload: String::new(),
+        })
+    }
+
+    async fn submit(&self, prepared: &PreparedTransaction) -> Result<(), AppError> {
+        let pending = self
+            .pending
+            .lock()
+            .unwrap()
+            .remove(&prepared.tx_hash)
+            .ok_or_else(|| {
+                AppError::Stellar(format!("Transaction {} was not prepared", prepared.tx_hash))
+            })?;
+        let PendingDisbursement {
+            campaign_id,
+            recipient,
+            amount,
+            purpose,
+            evidence,
+            args,
+        } = pending;
+
+        self.run(move |client| {
+            let mut evidence_vec = SorobanVec::new(&client.env);
+            for hash in &evidence {
+                evidence_vec.push_back(BytesN::from_array(&client.env, hash));
+            }
+            client
+                .try_disburse(
+                    &campaign_id,
+                    &Address::from_str(&client.env, &recipient),
+                    &amount,
+                    &SorobanString::from_str(&client.env, &purpose),
+                    &evidence_vec,
+                )
+                .map_err(contract_error)?
+                .map_err(conversion_error)
+        })
+        .await?;
+
+        self.record_as(&prepared.tx_hash, "disburse", args);
+        Ok(())
+    }
+
+    async fn close_campaign(&self, campaign_id: u32) -> Result<(), AppError> {
+        self.run(move |client| {
+            client
+                .try_close_campaign(&campaign_id)
+                .map_err(contract_error)?
+                .map_err(conversion_error)
+        })
+        .await?;
+        self.record("close_campaign", vec![json!(campaign_id)]);
+
+        Ok(())
+    }
+
+    async fn get_campaign(&self, campaign_id: u32) -> Result<serde_json::Value, AppError> {
+        self.run(move |client| {
+            let campaign = client
+                .try_get_campaign(&campaign_id)
+                .map_err(contract_error)?
+                .map_err(conversion_error)?;
+
+            Ok(json!({
+                "id": campaign.id,
+                "name": campaign.name.to_string(),
+                "org": campaign.org.to_string().to_string(),
+                "goal": campaign.goal.to_string(),
+                "raised": campaign.raised.to_string(),
+                "deadline": campaign.deadline,
+                "active": campaign.active,
+                "parent": campaign.parent,
+            }))
+        })
+        .await
+    }
+
+    async fn get_donations(&self, campaign_id: u32) -> Result<Vec<serde_json::Value>, AppError> {
+        self.run(move |client| {
+            let donations = client
+                .try_get_donations(&campaign_id)
+                .map_err(contract_error)?
+                .map_err(conversion_error)?;
+
+            Ok(donations
+                .iter()
+                .map(|donation| {
+                    json!({
+                        "donor": donation.donor.to_string().to_string(),
+                        "amount": donation.amount.to_string(),
+                        "timestamp": donation.timestamp,
+                    })
+                })
+                .collect())
+        })
+        .await
+    }
+
+    async fn get_transaction(&self, tx_hash: &str) -> Result<Option<ChainTransaction>, AppError> {
+        Ok(self.transactions.lock().unwrap().get(tx_hash).cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::mock_chain::MockChain;
+    use std::future::Future;
+
+    const EVIDENCE: &str = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+
+    fn account(seed: u8) -> String {
+        stellar_strkey::ed25519::PublicKey([seed; 32]).to_string()
+    }
+
+    #[tokio::test]
+    async fn in_process_chain_runs_the_donation_contract() {
+        let chain = InProcessChain::new();
+        let deadline = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() + 86_400;
+
+        let id = chain
+            .create_campaign(&account(1), "Water", 1_000, deadline)
+            .await
+            .unwrap() as u32;
+        chain.donate(id, &account(2), 600).await.unwrap();
+        chain
+            .disburse(id, &account(3), 250, "Filters", &[EVIDENCE.to_string()])
+            .await
+            .unwrap();
+
+        let campaign = chain.get_campaign(id).await.unwrap();
+        assert_eq!(campaign["org"], account(1));
+        assert_eq!(campaign["raised"], "350");
+
+        chain.close_campaign(id).await.unwrap();
+        assert!(matches!(
+            chain.donate(id, &account(2), 1).await,
+            Err(AppError::Stellar(_))
+        ));
+    }
+
+    /// Whether an overdraw, a payout after closing and a payout while
+    /// disputed go through, in that order
+    async fn payout_outcomes<F, Fut>(chain: &dyn ChainBackend, open_dispute: F) -> Vec<bool>
+    where
+        F: Fn(u32) -> Fut,
+        Fut: Future<Output = Result<(), AppError>>,
+    {
+        let deadline = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() + 86_400;
+        let id = chain
+            .create_campaign(&account(1), "Water", 1_000, deadline)
+            .await
+            .unwrap() as u32;
+        chain.donate(id, &account(2), 600).await.unwrap();
+        let evidence = [EVIDENCE.to_string()];
+
+        let overdrawn = chain.disburse(id, &account(3), 700, "Filters", &evidence).await;
+        chain.close_campaign(id).await.unwrap();
+        let after_close = chain.disburse(id, &account(3), 100, "Filters", &evidence).await;
+        open_dispute(id).await.unwrap();
+        let disputed = chain.disburse(id, &account(3), 100, "Filters", &evidence).await;
+
+        vec![overdrawn.is_ok(), after_close.is_ok(), disputed.is_ok()]
+    }
+
+    #[tokio::test]
+    async fn mock_chain_follows_the_contract_payout_rules() {
+        let contract = InProcessChain::new();
+        let mock = MockChain::new();
+
+        let expected = payout_outcomes(&contract, |id| contract.open_dispute(id)).await;
+        assert_eq!(expected, vec![false, true, false]);
+        assert_eq!(payout_outcomes(&mock, |id| mock.open_dispute(id)).await, expected);
+    }
+}
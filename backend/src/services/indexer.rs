@@ -0,0 +1,819 @@
+use std::{sync::Arc, time::Duration};
+
+use serde_json::{json, Value};
+use sqlx::{PgConnection, PgPool};
+use stellar_xdr::curr::{Limits, ReadXdr, ScVal};
+
+use crate::{
+    error::AppError,
+    services::{
+        ledger,
+        soroban_rpc::{
+            scval_to_json, xdr_error, ContractEventResult, EventsStart, GetEventsResult,
+            SorobanRpcClient,
+        },
+    },
+    AppState,
+};
+
+/// Row in `chain_cursors` tracking this worker
+const CURSOR_NAME: &str = "contract_events";
+/// Events requested per `getEvents` call
+const PAGE_SIZE: u32 = 100;
+
+/// Contract events mirrored into Postgres
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContractEvent {
+    CampaignCreated {
+        campaign_id: i32,
+        org: String,
+        name: String,
+        goal: i64,
+        deadline: i64,
+        parent: Option<i32>,
+    },
+    DonationReceived {
+        campaign_id: i32,
+        donor: String,
+        amount: i64,
+    },
+    FundsDisbursed {
+        campaign_id: i32,
+        recipient: String,
+        amount: i64,
+        purpose: String,
+        evidence_hashes: Vec<String>,
+    },
+}
+
+/// Start polling contract events in the background.
+///
+/// Only runs against a real network: the mock backend does not publish
+/// events anywhere the indexer could read them.
+pub fn spawn(state: Arc<AppState>) {
+    if state.config.chain_backend != "rpc" || state.config.contract_id.is_empty() {
+        tracing::info!("Event indexer disabled for {} chain backend", state.config.chain_backend);
+        return;
+    }
+
+    tokio::spawn(async move {
+        let client = SorobanRpcClient::from_config(&state.config);
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(state.config.indexer_poll_interval_secs));
+
+        loop {
+            interval.tick().await;
+            match sync(&state, &client).await {
+                Ok(0) => {}
+                Ok(count) => tracing::info!("Indexed {} contract events", count),
+                // Retried on the next tick from the last committed cursor
+                Err(e) => tracing::warn!("Event indexer failed: {}", e),
+            }
+        }
+    });
+}
+
+/// Read every page of events after the saved cursor, returning how many were applied
+async fn sync(state: &AppState, client: &SorobanRpcClient) -> Result<usize, AppError> {
+    let contract_id = &state.config.contract_id;
+    let mut applied = 0;
+
+    loop {
+        let (cursor, last_ledger) = load_cursor(&state.db).await?;
+        let start_ledger = match (&cursor, last_ledger) {
+            (Some(_), _) => 0,
+            (None, ledger) if ledger > 0 => ledger as u32,
+            (None, _) => match state.config.indexer_start_ledger {
+                Some(ledger) => ledger,
+                None => client.get_health().await?.latest_ledger,
+            },
+        };
+        let start = match &cursor {
+            Some(cursor) => EventsStart::Cursor(cursor),
+            None => EventsStart::Ledger(start_ledger),
+        };
+
+        let page = match client.get_events(contract_id, start, PAGE_SIZE).await {
+            Ok(page) => page,
+            Err(e) => {
+                // The saved position may have aged out of the RPC's retention
+                // window; skip ahead to the oldest ledger it still has.
+                let health = client.get_health().await?;
+                let indexed_ledger = last_ledger.max(start_ledger as i64);
+                if indexed_ledger < health.oldest_ledger as i64 {
+                    tracing::warn!(
+                        "Event indexer gap: ledgers {}..{} are no longer available",
+                        indexed_ledger,
+                        health.oldest_ledger
+                    );
+                    skip_gap(&state.db, indexed_ledger, health.oldest_ledger as i64).await?;
+                    continue;
+                }
+                return Err(e);
+            }
+        };
+
+        applied += apply_page(&state.db, contract_id, &page, last_ledger).await?;
+
+        // Without a cursor the next call would start from the same ledger
+        if page.events.len() < PAGE_SIZE as usize || page.cursor.is_none() {
+            return Ok(applied);
+        }
+    }
+}
+
+/// Mirror one page of events and save the cursor after it in one transaction,
+/// so the saved position never runs ahead of (or behind) the applied events
+async fn apply_page(
+    db: &PgPool,
+    contract_id: &str,
+    page: &GetEventsResult,
+    last_ledger: i64,
+) -> Result<usize, AppError> {
+    let mut applied = 0;
+    let mut tx = db.begin().await?;
+    let mut newest_ledger = last_ledger;
+    for event in &page.events {
+        newest_ledger = newest_ledger.max(event.ledger as i64);
+        if !event.in_successful_contract_call || event.contract_id != contract_id {
+            continue;
+        }
+        match decode_event(event) {
+            Ok(Some(decoded)) => {
+                apply_event(&mut tx, &event.tx_hash, decoded).await?;
+                applied += 1;
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Skipping undecodable event {}: {}", event.id, e),
+        }
+    }
+    save_cursor(&mut *tx, page.cursor.clone(), newest_ledger).await?;
+    tx.commit().await?;
+
+    Ok(applied)
+}
+
+/// Move the cursor past ledgers the RPC no longer serves, recording the
+/// skipped range as an open `indexer_gap` discrepancy (`db_value` is the last
+/// indexed ledger, `chain_value` the first one still available) so its events
+/// can be backfilled from an archive node
+async fn skip_gap(db: &PgPool, indexed_ledger: i64, oldest_ledger: i64) -> Result<(), AppError> {
+    let mut tx = db.begin().await?;
+    sqlx::query(
+        r#"
+        INSERT INTO reconciliation_discrepancies (field, db_value, chain_value)
+        VALUES ('indexer_gap', $1, $2)
+        "#,
+    )
+    .bind(indexed_ledger.to_string())
+    .bind(oldest_ledger.to_string())
+    .execute(&mut *tx)
+    .await?;
+    save_cursor(&mut *tx, None, oldest_ledger).await?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+async fn load_cursor(db: &PgPool) -> Result<(Option<String>, i64), AppError> {
+    let row = sqlx::query_as::<_, (Option<String>, i64)>(
+        "SELECT cursor, last_ledger FROM chain_cursors WHERE name = $1",
+    )
+    .bind(CURSOR_NAME)
+    .fetch_optional(db)
+    .await?;
+
+    Ok(row.unwrap_or((None, 0)))
+}
+
+async fn save_cursor<'e, E>(db: E, cursor: Option<String>, last_ledger: i64) -> Result<(), AppError>
+where
+    E: sqlx::Executor<'e, Database = sqlx::Postgres>,
+{
+    sqlx::query(
+        r#"
+        INSERT INTO chain_cursors (name, cursor, last_ledger, updated_at)
+        VALUES ($1, $2, $3, NOW())
+        ON CONFLICT (name) DO UPDATE
+        SET cursor = EXCLUDED.cursor, last_ledger = EXCLUDED.last_ledger, updated_at = NOW()
+        "#,
+    )
+    .bind(CURSOR_NAME)
+    .bind(cursor)
+    .bind(last_ledger)
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
+
+/// Decode one of our contract events, ignoring event types the DB does not mirror
+pub fn decode_event(event: &ContractEventResult) -> Result<Option<ContractEvent>, AppError> {
+    let topics = event
+        .topic
+        .iter()
+        .map(|topic| ScVal::from_xdr_base64(topic, Limits::none()).map(|v| scval_to_json(&v)))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(xdr_error)?;
+    let body = scval_to_json(&ScVal::from_xdr_base64(&event.value, Limits::none()).map_err(xdr_error)?);
+
+    let (name, campaign_id, address) = match topics.as_slice() {
+        [Value::String(name), campaign_id, Value::String(address), ..] => {
+            (name.as_str(), as_i32(campaign_id)?, address.clone())
+        }
+        _ => return Ok(None),
+    };
+
+    let decoded = match name {
+        "campaign_created" => ContractEvent::CampaignCreated {
+            campaign_id,
+            org: address,
+            name: as_string(&body["name"])?,
+            goal: as_i64(&body["goal"])?,
+            deadline: as_i64(&body["deadline"])?,
+            parent: match &body["parent"] {
+                Value::Null => None,
+                parent => Some(as_i32(parent)?),
+            },
+        },
+        "donation_received" => ContractEvent::DonationReceived {
+            campaign_id,
+            donor: address,
+            amount: as_i64(&body["amount"])?,
+        },
+        "funds_disbursed" => ContractEvent::FundsDisbursed {
+            campaign_id,
+            recipient: address,
+            amount: as_i64(&body["amount"])?,
+            purpose: as_string(&body["purpose"])?,
+            evidence_hashes: body["evidence"]
+                .as_array()
+                .map(|hashes| hashes.iter().filter_map(|h| h.as_str().map(String::from)).collect())
+                .unwrap_or_default(),
+        },
+        _ => return Ok(None),
+    };
+
+    Ok(Some(decoded))
+}
+
+fn malformed(value: &Value) -> AppError {
+    AppError::Stellar(format!("Malformed event field {}", value))
+}
+
+fn as_i32(value: &Value) -> Result<i32, AppError> {
+    value
+        .as_u64()
+        .and_then(|n| i32::try_from(n).ok())
+        .ok_or_else(|| malformed(value))
+}
+
+/// Amounts arrive as decimal strings (i128) and timestamps as numbers (u64)
+fn as_i64(value: &Value) -> Result<i64, AppError> {
+    match value {
+        Value::String(s) => s.parse().ok(),
+        other => other.as_i64(),
+    }
+    .ok_or_else(|| malformed(value))
+}
+
+fn as_string(value: &Value) -> Result<String, AppError> {
+    value.as_str().map(String::from).ok_or_else(|| malformed(value))
+}
+
+/// Mirror an event into the existing tables; replaying an event is a no-op
+async fn apply_event(
+    conn: &mut PgConnection,
+    tx_hash: &str,
+    event: ContractEvent,
+) -> Result<(), AppError> {
+    match event {
+        ContractEvent::CampaignCreated {
+            campaign_id,
+            org,
+            name,
+            goal,
+            deadline,
+            parent: None,
+        } => {
+            // Campaigns of organizations we do not know are left alone
+            sqlx::query(
+                r#"
+                INSERT INTO campaigns (name, org_id, goal_amount, deadline, contract_campaign_id, status)
+                SELECT $1, o.id, $2, to_timestamp($3), $4, 'active'
+                FROM organizations o
+                WHERE o.wallet_address = $5
+                AND NOT EXISTS (SELECT 1 FROM campaigns WHERE contract_campaign_id = $4)
+                LIMIT 1
+                "#,
+            )
+            .bind(&name)
+            .bind(goal)
+            .bind(deadline as f64)
+            .bind(campaign_id)
+            .bind(&org)
+            .execute(&mut *conn)
+            .await?;
+        }
+        ContractEvent::CampaignCreated {
+            campaign_id,
+            name,
+            goal,
+            deadline,
+            parent: Some(parent),
+            ..
+        } => {
+            // Fundraiser pages belong to their parent's organization, so
+            // donations to them can roll up into the parent
+            sqlx::query(
+                r#"
+                INSERT INTO campaigns
+                    (name, org_id, goal_amount, deadline, contract_campaign_id, status, parent_id)
+                SELECT $1, p.org_id, $2, to_timestamp($3), $4, 'active', p.id
+                FROM campaigns p
+                WHERE p.contract_campaign_id = $5
+                AND NOT EXISTS (SELECT 1 FROM campaigns WHERE contract_campaign_id = $4)
+                ORDER BY p.created_at DESC
+                LIMIT 1
+                "#,
+            )
+            .bind(&name)
+            .bind(goal)
+            .bind(deadline as f64)
+            .bind(campaign_id)
+            .bind(parent)
+            .execute(&mut *conn)
+            .await?;
+        }
+        ContractEvent::DonationReceived {
+            campaign_id,
+            donor,
+            amount,
+        } => {
+            // Several rows may share a contract id after a re-import; the
+            // newest one is the live campaign and the only one credited
+            let campaign = sqlx::query_scalar::<_, uuid::Uuid>(
+                r#"
+                SELECT id FROM campaigns
+                WHERE contract_campaign_id = $1
+                ORDER BY created_at DESC
+                LIMIT 1
+                "#,
+            )
+            .bind(campaign_id)
+            .fetch_optional(&mut *conn)
+            .await?;
+
+            if let Some(campaign_id) = campaign {
+                if let Some(donation) =
+                    ledger::credit_donation(conn, campaign_id, &donor, amount, tx_hash).await?
+                {
+                    log_indexed(conn, "donation", donation.id, &donor, tx_hash, amount).await?;
+                }
+            }
+        }
+        ContractEvent::FundsDisbursed {
+            campaign_id,
+            recipient,
+            amount,
+            purpose,
+            evidence_hashes,
+        } => {
+            // A payout the API submitted carries its hash from before it was
+            // sent, and its funds were reserved then
+            let settled = sqlx::query_scalar::<_, uuid::Uuid>(
+                r#"
+                UPDATE disbursements
+                SET status = 'executed', executed_at = NOW()
+                WHERE tx_hash = $1 AND status = 'executing'
+                RETURNING id
+                "#,
+            )
+            .bind(tx_hash)
+            .fetch_optional(&mut *conn)
+            .await?;
+            if let Some(disbursement_id) = settled {
+                log_indexed(conn, "disbursement", disbursement_id, &recipient, tx_hash, amount).await?;
+                return Ok(());
+            }
+
+            let known = sqlx::query_scalar::<_, bool>(
+                "SELECT EXISTS(SELECT 1 FROM disbursements WHERE tx_hash = $1)",
+            )
+            .bind(tx_hash)
+            .fetch_one(&mut *conn)
+            .await?;
+            if known {
+                return Ok(());
+            }
+
+            // A payout the API did not submit is recorded as its own
+            // disbursement; guessing which request it paid could settle the wrong one
+            let inserted = sqlx::query_as::<_, (uuid::Uuid, uuid::Uuid)>(
+                r#"
+                INSERT INTO disbursements
+                    (campaign_id, recipient_address, amount, purpose, evidence_hashes, status, tx_hash, executed_at)
+                SELECT id, $2, $3, $4, $5, 'executed', $6, NOW() FROM campaigns
+                WHERE contract_campaign_id = $1
+                ORDER BY created_at DESC
+                LIMIT 1
+                RETURNING id, campaign_id
+                "#,
+            )
+            .bind(campaign_id)
+            .bind(&recipient)
+            .bind(amount)
+            .bind(&purpose)
+            .bind(&evidence_hashes)
+            .bind(tx_hash)
+            .fetch_optional(&mut *conn)
+            .await?;
+
+            if let Some((disbursement_id, campaign_id)) = inserted {
+                sqlx::query(
+                    r#"
+                    UPDATE campaigns
+                    SET raised_amount = raised_amount - $1, updated_at = NOW()
+                    WHERE id = $2
+                    "#,
+                )
+                .bind(amount)
+                .bind(campaign_id)
+                .execute(&mut *conn)
+                .await?;
+
+                log_indexed(conn, "disbursement", disbursement_id, &recipient, tx_hash, amount).await?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn log_indexed(
+    conn: &mut PgConnection,
+    entity_type: &str,
+    entity_id: uuid::Uuid,
+    actor_address: &str,
+    tx_hash: &str,
+    amount: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO audit_logs (entity_type, entity_id, action, actor_address, details)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+    )
+    .bind(entity_type)
+    .bind(entity_id)
+    .bind("indexed")
+    .bind(actor_address)
+    .bind(json!({
+        "tx_hash": tx_hash,
+        "amount": amount
+    }))
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stellar_xdr::curr::{
+        ScAddress, ScBytes, ScMap, ScMapEntry, ScString, ScSymbol, ScVec, WriteXdr,
+    };
+
+    const DONOR: &str = "GBRPYHIL2CI3FNQ4BXLFMNDLFJUNPU2HY3ZMFSHONUCEOASW7QC7OX2H";
+    const CONTRACT: &str = "CCONTRACT";
+
+    fn symbol(name: &str) -> ScVal {
+        ScVal::Symbol(ScSymbol(name.try_into().unwrap()))
+    }
+
+    fn event(topics: Vec<ScVal>, body: Vec<(&str, ScVal)>) -> ContractEventResult {
+        let body = ScVal::Map(Some(ScMap(
+            body.into_iter()
+                .map(|(key, val)| ScMapEntry { key: symbol(key), val })
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap(),
+        )));
+        ContractEventResult {
+            id: "0000000001-0000000001".to_string(),
+            ledger: 1,
+            contract_id: String::new(),
+            tx_hash: "abc".to_string(),
+            topic: topics
+                .iter()
+                .map(|t| t.to_xdr_base64(Limits::none()).unwrap())
+                .collect(),
+            value: body.to_xdr_base64(Limits::none()).unwrap(),
+            in_successful_contract_call: true,
+        }
+    }
+
+    fn donor() -> ScVal {
+        ScVal::Address(DONOR.parse::<ScAddress>().unwrap())
+    }
+
+    #[test]
+    fn decodes_donation_and_disbursement_events() {
+        let donation = event(
+            vec![symbol("donation_received"), ScVal::U32(4), donor()],
+            vec![("amount", ScVal::from(2_500i128))],
+        );
+        assert_eq!(
+            decode_event(&donation).unwrap(),
+            Some(ContractEvent::DonationReceived {
+                campaign_id: 4,
+                donor: DONOR.to_string(),
+                amount: 2_500,
+            })
+        );
+
+        let disbursement = event(
+            vec![symbol("funds_disbursed"), ScVal::U32(4), donor()],
+            vec![
+                ("amount", ScVal::from(1_000i128)),
+                (
+                    "evidence",
+                    ScVal::Vec(Some(ScVec(
+                        vec![ScVal::Bytes(ScBytes(vec![0xab; 32].try_into().unwrap()))]
+                            .try_into()
+                            .unwrap(),
+                    ))),
+                ),
+                ("purpose", ScVal::String(ScString("Filters".try_into().unwrap()))),
+            ],
+        );
+        assert_eq!(
+            decode_event(&disbursement).unwrap(),
+            Some(ContractEvent::FundsDisbursed {
+                campaign_id: 4,
+                recipient: DONOR.to_string(),
+                amount: 1_000,
+                purpose: "Filters".to_string(),
+                evidence_hashes: vec!["ab".repeat(32)],
+            })
+        );
+    }
+
+    #[test]
+    fn ignores_events_the_db_does_not_mirror() {
+        let claimed = event(
+            vec![symbol("share_claimed"), ScVal::U32(4), donor()],
+            vec![("amount", ScVal::from(1i128))],
+        );
+        assert_eq!(decode_event(&claimed).unwrap(), None);
+    }
+
+    #[test]
+    fn rejects_amounts_that_do_not_fit_the_db() {
+        let donation = event(
+            vec![symbol("donation_received"), ScVal::U32(4), donor()],
+            vec![("amount", ScVal::from(i128::MAX))],
+        );
+        assert!(decode_event(&donation).is_err());
+    }
+
+    /// A campaign mirrored from contract campaign 4, created `age_days` ago
+    async fn campaign(db: &PgPool, raised: i64, goal: i64, age_days: i32) -> uuid::Uuid {
+        let org_id = sqlx::query_scalar::<_, uuid::Uuid>(
+            "INSERT INTO organizations (name, wallet_address) VALUES ('Relief', $1) RETURNING id",
+        )
+        .bind(format!("G{}", uuid::Uuid::new_v4().simple()))
+        .fetch_one(db)
+        .await
+        .unwrap();
+
+        sqlx::query_scalar::<_, uuid::Uuid>(
+            r#"
+            INSERT INTO campaigns
+                (name, org_id, goal_amount, raised_amount, deadline, contract_campaign_id, status, created_at)
+            VALUES ('Water', $1, $2, $3, NOW() + INTERVAL '30 days', 4, 'active',
+                    NOW() - make_interval(days => $4))
+            RETURNING id
+            "#,
+        )
+        .bind(org_id)
+        .bind(goal)
+        .bind(raised)
+        .bind(age_days)
+        .fetch_one(db)
+        .await
+        .unwrap()
+    }
+
+    async fn raised(db: &PgPool, campaign_id: uuid::Uuid) -> (i64, String) {
+        sqlx::query_as::<_, (Option<i64>, String)>(
+            "SELECT raised_amount, status FROM campaigns WHERE id = $1",
+        )
+        .bind(campaign_id)
+        .fetch_one(db)
+        .await
+        .map(|(raised, status)| (raised.unwrap_or(0), status))
+        .unwrap()
+    }
+
+    /// An event emitted by our contract in transaction `tx_hash`
+    fn emitted(mut event: ContractEventResult, tx_hash: String) -> ContractEventResult {
+        event.contract_id = CONTRACT.to_string();
+        event.tx_hash = tx_hash;
+        event
+    }
+
+    fn donation(tx_hash: String, amount: i128) -> ContractEventResult {
+        donation_to(4, tx_hash, amount)
+    }
+
+    fn donation_to(contract_campaign_id: u32, tx_hash: String, amount: i128) -> ContractEventResult {
+        emitted(
+            event(
+                vec![symbol("donation_received"), ScVal::U32(contract_campaign_id), donor()],
+                vec![("amount", ScVal::from(amount))],
+            ),
+            tx_hash,
+        )
+    }
+
+    /// Fundraiser page `contract_campaign_id` created under contract campaign 4
+    fn fundraiser_page(contract_campaign_id: u32, tx_hash: String) -> ContractEventResult {
+        emitted(
+            event(
+                vec![symbol("campaign_created"), ScVal::U32(contract_campaign_id), donor()],
+                vec![
+                    ("deadline", ScVal::U64(4_000_000_000)),
+                    ("goal", ScVal::from(1_000i128)),
+                    ("name", ScVal::String(ScString("Marathon".try_into().unwrap()))),
+                    ("parent", ScVal::U32(4)),
+                ],
+            ),
+            tx_hash,
+        )
+    }
+
+    fn payout(tx_hash: String, amount: i128) -> ContractEventResult {
+        emitted(
+            event(
+                vec![symbol("funds_disbursed"), ScVal::U32(4), donor()],
+                vec![
+                    ("amount", ScVal::from(amount)),
+                    ("evidence", ScVal::Vec(Some(ScVec(vec![].try_into().unwrap())))),
+                    ("purpose", ScVal::String(ScString("Filters".try_into().unwrap()))),
+                ],
+            ),
+            tx_hash,
+        )
+    }
+
+    fn page(events: Vec<ContractEventResult>) -> GetEventsResult {
+        GetEventsResult {
+            events,
+            cursor: Some("0000000001-0000000002".to_string()),
+        }
+    }
+
+    #[sqlx::test]
+    async fn replaying_a_page_counts_each_donation_once(db: PgPool) {
+        let campaign_id = campaign(&db, 0, 3_000, 0).await;
+        let page = page(vec![
+            donation("aa".repeat(32), 2_000),
+            donation("bb".repeat(32), 1_000),
+        ]);
+
+        apply_page(&db, CONTRACT, &page, 0).await.unwrap();
+        apply_page(&db, CONTRACT, &page, 0).await.unwrap();
+
+        assert_eq!(raised(&db, campaign_id).await, (3_000, "completed".to_string()));
+        let donations = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM donations")
+            .fetch_one(&db)
+            .await
+            .unwrap();
+        assert_eq!(donations, 2);
+        assert_eq!(
+            load_cursor(&db).await.unwrap(),
+            (Some("0000000001-0000000002".to_string()), 1)
+        );
+    }
+
+    #[sqlx::test]
+    async fn events_touch_only_the_newest_campaign_with_a_contract_id(db: PgPool) {
+        let stale = campaign(&db, 0, 10_000, 7).await;
+        let live = campaign(&db, 0, 10_000, 0).await;
+
+        let page = page(vec![donation("aa".repeat(32), 500), payout("bb".repeat(32), 200)]);
+        apply_page(&db, CONTRACT, &page, 0).await.unwrap();
+
+        assert_eq!(raised(&db, live).await.0, 300);
+        assert_eq!(raised(&db, stale).await.0, 0);
+    }
+
+    async fn disbursement(
+        db: &PgPool,
+        campaign_id: uuid::Uuid,
+        status: &str,
+        tx_hash: Option<String>,
+    ) -> uuid::Uuid {
+        sqlx::query_scalar::<_, uuid::Uuid>(
+            r#"
+            INSERT INTO disbursements (campaign_id, recipient_address, amount, purpose, status, tx_hash)
+            VALUES ($1, $2, 400, 'Filters', $3, $4)
+            RETURNING id
+            "#,
+        )
+        .bind(campaign_id)
+        .bind(DONOR)
+        .bind(status)
+        .bind(tx_hash)
+        .fetch_one(db)
+        .await
+        .unwrap()
+    }
+
+    async fn disbursements(db: &PgPool) -> Vec<(uuid::Uuid, String, Option<String>)> {
+        sqlx::query_as::<_, (uuid::Uuid, String, Option<String>)>(
+            "SELECT id, status, tx_hash FROM disbursements",
+        )
+        .fetch_all(db)
+        .await
+        .unwrap()
+    }
+
+    #[sqlx::test]
+    async fn a_payout_with_an_unknown_hash_is_recorded_on_its_own(db: PgPool) {
+        let campaign_id = campaign(&db, 1_000, 10_000, 0).await;
+        let approved = disbursement(&db, campaign_id, "approved", None).await;
+
+        let page = page(vec![payout("cc".repeat(32), 400)]);
+        apply_page(&db, CONTRACT, &page, 0).await.unwrap();
+        apply_page(&db, CONTRACT, &page, 0).await.unwrap();
+
+        // The approved request with the same recipient and amount is not guessed at
+        let mut rows = disbursements(&db).await;
+        rows.sort_by_key(|(id, _, _)| *id != approved);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0], (approved, "approved".to_string(), None));
+        assert_eq!((rows[1].1.as_str(), rows[1].2.clone()), ("executed", Some("cc".repeat(32))));
+        assert_eq!(raised(&db, campaign_id).await.0, 600);
+    }
+
+    #[sqlx::test]
+    async fn a_payout_settles_an_executing_disbursement_without_debiting_again(db: PgPool) {
+        // The API debited the campaign when it reserved the funds
+        let campaign_id = campaign(&db, 600, 10_000, 0).await;
+        let approved = disbursement(&db, campaign_id, "approved", None).await;
+        let executing = disbursement(&db, campaign_id, "executing", Some("dd".repeat(32))).await;
+
+        let page = page(vec![payout("dd".repeat(32), 400)]);
+        apply_page(&db, CONTRACT, &page, 0).await.unwrap();
+
+        let mut rows = disbursements(&db).await;
+        rows.sort_by_key(|(id, _, _)| *id != executing);
+        assert_eq!(
+            rows,
+            vec![
+                (executing, "executed".to_string(), Some("dd".repeat(32))),
+                (approved, "approved".to_string(), None),
+            ]
+        );
+        assert_eq!(raised(&db, campaign_id).await.0, 600);
+    }
+
+    #[sqlx::test]
+    async fn a_donation_to_a_fundraiser_page_also_credits_its_parent(db: PgPool) {
+        let parent = campaign(&db, 0, 10_000, 0).await;
+        let page = page(vec![
+            fundraiser_page(5, "aa".repeat(32)),
+            donation_to(5, "bb".repeat(32), 1_200),
+        ]);
+
+        apply_page(&db, CONTRACT, &page, 0).await.unwrap();
+        apply_page(&db, CONTRACT, &page, 0).await.unwrap();
+
+        let (child, child_parent, child_raised) = sqlx::query_as::<_, (uuid::Uuid, Option<uuid::Uuid>, i64)>(
+            "SELECT id, parent_id, raised_amount FROM campaigns WHERE contract_campaign_id = 5",
+        )
+        .fetch_one(&db)
+        .await
+        .unwrap();
+        assert_eq!(child_parent, Some(parent));
+        assert_eq!(child_raised, 1_200);
+        assert_eq!(raised(&db, child).await.1, "completed");
+        assert_eq!(raised(&db, parent).await, (1_200, "active".to_string()));
+    }
+
+    #[sqlx::test]
+    async fn a_skipped_ledger_range_is_kept_as_a_discrepancy(db: PgPool) {
+        skip_gap(&db, 10, 50).await.unwrap();
+
+        let gap = sqlx::query_as::<_, (Option<uuid::Uuid>, String, String, String)>(
+            "SELECT campaign_id, field, db_value, chain_value FROM reconciliation_discrepancies WHERE resolved_at IS NULL",
+        )
+        .fetch_one(&db)
+        .await
+        .unwrap();
+        assert_eq!(gap, (None, "indexer_gap".to_string(), "10".to_string(), "50".to_string()));
+        assert_eq!(load_cursor(&db).await.unwrap(), (None, 50));
+    }
+}
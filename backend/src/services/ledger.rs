@@ -0,0 +1,569 @@
+use serde_json::json;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::{
+    error::AppError,
+    models::{Disbursement, Donation},
+    services::chain::{self, ChainBackend, PreparedTransaction},
+};
+
+/// Record a verified donation and credit its campaign in one transaction.
+///
+/// The balance is incremented in SQL, so concurrent donations to the same
+/// campaign cannot overwrite each other, and a transaction hash is only ever
+/// counted once, whatever its hex case.
+pub async fn record_donation(
+    db: &PgPool,
+    campaign_id: Uuid,
+    donor_address: &str,
+    amount: i64,
+    tx_hash: &str,
+) -> Result<Donation, AppError> {
+    let mut tx = db.begin().await?;
+
+    let donation = credit_donation(&mut tx, campaign_id, donor_address, amount, tx_hash)
+        .await?
+        .ok_or_else(|| AppError::BadRequest("Donation transaction already recorded".to_string()))?;
+
+    // Log audit
+    sqlx::query(
+        r#"
+        INSERT INTO audit_logs (entity_type, entity_id, action, actor_address, details)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+    )
+    .bind("donation")
+    .bind(donation.id)
+    .bind("created")
+    .bind(donor_address)
+    .bind(json!({
+        "campaign_id": campaign_id,
+        "amount": amount,
+        "tx_hash": &donation.tx_hash
+    }))
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(donation)
+}
+
+/// Insert a donation and credit its campaign on `conn`, plus the parent
+/// campaign when it went to a fundraiser page.
+///
+/// Returns `None` when the transaction hash is already recorded, in which
+/// case nothing is credited. The caller owns the surrounding transaction.
+pub async fn credit_donation(
+    conn: &mut PgConnection,
+    campaign_id: Uuid,
+    donor_address: &str,
+    amount: i64,
+    tx_hash: &str,
+) -> Result<Option<Donation>, AppError> {
+    let tx_hash = chain::normalize_tx_hash(tx_hash)?;
+
+    let donation = match sqlx::query_as::<_, Donation>(
+        r#"
+        INSERT INTO donations (campaign_id, donor_address, amount, tx_hash)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (tx_hash) DO NOTHING
+        RETURNING *
+        "#,
+    )
+    .bind(campaign_id)
+    .bind(donor_address)
+    .bind(amount)
+    .bind(&tx_hash)
+    .fetch_optional(&mut *conn)
+    .await?
+    {
+        Some(donation) => donation,
+        None => return Ok(None),
+    };
+
+    // Credit the campaign, and its parent for a fundraiser page as the
+    // contract does, completing each once its goal is reached
+    sqlx::query(
+        r#"
+        UPDATE campaigns
+        SET raised_amount = COALESCE(raised_amount, 0) + $1,
+            status = CASE
+                WHEN status = 'active' AND COALESCE(raised_amount, 0) + $1 >= goal_amount
+                THEN 'completed'
+                ELSE status
+            END,
+            updated_at = NOW()
+        WHERE id = $2
+        OR id = (SELECT parent_id FROM campaigns WHERE id = $2)
+        "#,
+    )
+    .bind(amount)
+    .bind(campaign_id)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(Some(donation))
+}
+
+/// Release an approved disbursement and debit its campaign.
+///
+/// Funds are reserved first: under row locks the disbursement moves to
+/// `executing` and the campaign is debited, so a disbursement executes once
+/// and parallel disbursements from one campaign cannot together overdraw it.
+/// The payout is then prepared, its hash stored on the row and submitted,
+/// with no transaction open. If preparing fails nothing was sent, so the
+/// disbursement returns to `approved` with the funds credited back. Once
+/// submitted, a failure leaves the outcome unknown: the row stays
+/// `executing` until the indexer sees the payout or reconciliation finds the
+/// transaction failed. Campaigns without an on-chain counterpart record
+/// `fallback_tx_hash` instead of calling the contract.
+pub async fn execute_disbursement(
+    db: &PgPool,
+    chain: &dyn ChainBackend,
+    id: Uuid,
+    fallback_tx_hash: &str,
+    actor_address: &str,
+) -> Result<Disbursement, AppError> {
+    let (disbursement, contract_campaign_id) = reserve_disbursement(db, id).await?;
+
+    let tx_hash = match contract_campaign_id {
+        Some(contract_campaign_id) => {
+            let prepared = match prepare_payout(db, chain, &disbursement, contract_campaign_id).await {
+                Ok(prepared) => prepared,
+                Err(e) => {
+                    release_disbursement(db, &disbursement).await?;
+                    return Err(e);
+                }
+            };
+
+            if let Err(e) = chain.submit(&prepared).await {
+                tracing::warn!(
+                    "Disbursement {} stays executing, payout {} unconfirmed: {}",
+                    id,
+                    prepared.tx_hash,
+                    e
+                );
+                return Err(AppError::Stellar(format!(
+                    "Payout {} was submitted but not confirmed; the disbursement completes once it is seen on-chain: {}",
+                    prepared.tx_hash, e
+                )));
+            }
+            prepared.tx_hash
+        }
+        None => fallback_tx_hash.to_string(),
+    };
+
+    let mut tx = db.begin().await?;
+
+    // The indexer may already have matched the payout event to this row
+    let updated = match sqlx::query_as::<_, Disbursement>(
+        r#"
+        UPDATE disbursements
+        SET status = 'executed', tx_hash = $1, executed_at = NOW()
+        WHERE id = $2 AND status = 'executing'
+        RETURNING *
+        "#,
+    )
+    .bind(&tx_hash)
+    .bind(id)
+    .fetch_optional(&mut *tx)
+    .await?
+    {
+        Some(updated) => updated,
+        None => {
+            sqlx::query_as::<_, Disbursement>("SELECT * FROM disbursements WHERE id = $1")
+                .bind(id)
+                .fetch_one(&mut *tx)
+                .await?
+        }
+    };
+
+    // Log audit
+    sqlx::query(
+        r#"
+        INSERT INTO audit_logs (entity_type, entity_id, action, actor_address, details)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+    )
+    .bind("disbursement")
+    .bind(id)
+    .bind("executed")
+    .bind(actor_address)
+    .bind(json!({
+        "tx_hash": &tx_hash,
+        "amount": disbursement.amount
+    }))
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(updated)
+}
+
+/// Move an approved disbursement to `executing` and debit its campaign,
+/// returning it with the campaign's contract id
+async fn reserve_disbursement(
+    db: &PgPool,
+    id: Uuid,
+) -> Result<(Disbursement, Option<i32>), AppError> {
+    let mut tx = db.begin().await?;
+
+    let disbursement = sqlx::query_as::<_, Disbursement>(
+        "SELECT * FROM disbursements WHERE id = $1 FOR UPDATE"
+    )
+    .bind(id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Disbursement not found".to_string()))?;
+
+    match disbursement.status.as_str() {
+        "approved" => {}
+        "executing" => {
+            return Err(AppError::BadRequest(
+                "Disbursement is already being executed".to_string(),
+            ))
+        }
+        _ => {
+            return Err(AppError::BadRequest(
+                "Disbursement must be approved first".to_string(),
+            ))
+        }
+    }
+
+    let (raised_amount, contract_campaign_id) = sqlx::query_as::<_, (Option<i64>, Option<i32>)>(
+        "SELECT raised_amount, contract_campaign_id FROM campaigns WHERE id = $1 FOR UPDATE",
+    )
+    .bind(disbursement.campaign_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    if disbursement.amount > raised_amount.unwrap_or(0) {
+        return Err(AppError::BadRequest(
+            "Insufficient funds in campaign".to_string(),
+        ));
+    }
+
+    sqlx::query("UPDATE disbursements SET status = 'executing' WHERE id = $1")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query(
+        r#"
+        UPDATE campaigns
+        SET raised_amount = raised_amount - $1, updated_at = NOW()
+        WHERE id = $2
+        "#,
+    )
+    .bind(disbursement.amount)
+    .bind(disbursement.campaign_id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok((disbursement, contract_campaign_id))
+}
+
+/// Prepare the payout and store its hash on the reserved row before it is
+/// sent, so the payout can be matched to the disbursement whatever happens next
+async fn prepare_payout(
+    db: &PgPool,
+    chain: &dyn ChainBackend,
+    disbursement: &Disbursement,
+    contract_campaign_id: i32,
+) -> Result<PreparedTransaction, AppError> {
+    let prepared = chain
+        .prepare_disbursement(
+            contract_campaign_id as u32,
+            &disbursement.recipient_address,
+            disbursement.amount,
+            &disbursement.purpose,
+            &disbursement.evidence_hashes,
+        )
+        .await?;
+
+    sqlx::query("UPDATE disbursements SET tx_hash = $1 WHERE id = $2 AND status = 'executing'")
+        .bind(&prepared.tx_hash)
+        .bind(disbursement.id)
+        .execute(db)
+        .await?;
+
+    Ok(prepared)
+}
+
+/// Undo [`reserve_disbursement`] for a payout that was never submitted or
+/// that failed on-chain
+pub async fn release_disbursement(db: &PgPool, disbursement: &Disbursement) -> Result<(), AppError> {
+    let mut tx = db.begin().await?;
+
+    let released = sqlx::query(
+        "UPDATE disbursements SET status = 'approved', tx_hash = NULL WHERE id = $1 AND status = 'executing'",
+    )
+    .bind(disbursement.id)
+    .execute(&mut *tx)
+    .await?;
+
+    // Nothing to credit back if the payout was settled in the meantime
+    if released.rows_affected() > 0 {
+        sqlx::query(
+            r#"
+            UPDATE campaigns
+            SET raised_amount = raised_amount + $1, updated_at = NOW()
+            WHERE id = $2
+            "#,
+        )
+        .bind(disbursement.amount)
+        .bind(disbursement.campaign_id)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::mock_chain::MockChain;
+    use std::sync::Arc;
+    use tokio::task::JoinHandle;
+
+    const DONOR: &str = "GBRPYHIL2CI3FNQ4BXLFMNDLFJUNPU2HY3ZMFSHONUCEOASW7QC7OX2H";
+    const ORG: &str = "GAAZI4TCR3TY5OJHCTJC2A4QSY6CJWJH5IAJTGKIN2ER7LBNVKOCCWN7";
+    const ACTOR: &str = "GOWNER";
+
+    /// An active off-chain campaign holding `raised` of a `goal`
+    async fn campaign(db: &PgPool, raised: i64, goal: i64) -> Uuid {
+        let org_id = sqlx::query_scalar::<_, Uuid>(
+            "INSERT INTO organizations (name, wallet_address) VALUES ('Relief', $1) RETURNING id",
+        )
+        .bind(format!("G{}", Uuid::new_v4().simple()))
+        .fetch_one(db)
+        .await
+        .unwrap();
+
+        sqlx::query_scalar::<_, Uuid>(
+            r#"
+            INSERT INTO campaigns (name, org_id, goal_amount, raised_amount, deadline)
+            VALUES ('Water', $1, $2, $3, NOW() + INTERVAL '30 days')
+            RETURNING id
+            "#,
+        )
+        .bind(org_id)
+        .bind(goal)
+        .bind(raised)
+        .fetch_one(db)
+        .await
+        .unwrap()
+    }
+
+    async fn approved_disbursement(db: &PgPool, campaign_id: Uuid, amount: i64) -> Uuid {
+        sqlx::query_scalar::<_, Uuid>(
+            r#"
+            INSERT INTO disbursements (campaign_id, recipient_address, amount, purpose, evidence_hashes, status)
+            VALUES ($1, 'GRECIPIENT', $2, 'Water filters', $3, 'approved')
+            RETURNING id
+            "#,
+        )
+        .bind(campaign_id)
+        .bind(amount)
+        .bind(vec!["ab".repeat(32)])
+        .fetch_one(db)
+        .await
+        .unwrap()
+    }
+
+    /// Link a campaign to contract campaign `contract_campaign_id`
+    async fn link(db: &PgPool, campaign_id: Uuid, contract_campaign_id: u32) {
+        sqlx::query("UPDATE campaigns SET contract_campaign_id = $1 WHERE id = $2")
+            .bind(contract_campaign_id as i32)
+            .bind(campaign_id)
+            .execute(db)
+            .await
+            .unwrap();
+    }
+
+    async fn status(db: &PgPool, disbursement_id: Uuid) -> String {
+        sqlx::query_scalar::<_, String>("SELECT status FROM disbursements WHERE id = $1")
+            .bind(disbursement_id)
+            .fetch_one(db)
+            .await
+            .unwrap()
+    }
+
+    async fn raised(db: &PgPool, campaign_id: Uuid) -> (i64, String) {
+        sqlx::query_as::<_, (Option<i64>, String)>(
+            "SELECT raised_amount, status FROM campaigns WHERE id = $1",
+        )
+        .bind(campaign_id)
+        .fetch_one(db)
+        .await
+        .map(|(raised, status)| (raised.unwrap_or(0), status))
+        .unwrap()
+    }
+
+    /// Wait for every task and count the successes; the losers of a race
+    /// must fail with a client error, never anything else
+    async fn successes<T>(tasks: Vec<JoinHandle<Result<T, AppError>>>) -> usize {
+        let mut ok = 0;
+        for task in tasks {
+            match task.await.unwrap() {
+                Ok(_) => ok += 1,
+                Err(AppError::BadRequest(_)) => {}
+                Err(other) => panic!("unexpected error: {:?}", other),
+            }
+        }
+        ok
+    }
+
+    #[sqlx::test]
+    async fn parallel_donations_are_all_counted(db: PgPool) {
+        let campaign_id = campaign(&db, 0, 1_000).await;
+
+        let tasks = (1..=20i64)
+            .map(|amount| {
+                let db = db.clone();
+                tokio::spawn(async move {
+                    record_donation(&db, campaign_id, DONOR, amount, &format!("{:064x}", amount)).await
+                })
+            })
+            .collect();
+
+        assert_eq!(successes(tasks).await, 20);
+        assert_eq!(raised(&db, campaign_id).await, (210, "active".to_string()));
+    }
+
+    #[sqlx::test]
+    async fn a_donation_transaction_is_counted_once(db: PgPool) {
+        let campaign_id = campaign(&db, 0, 100).await;
+
+        let tasks = (0..8)
+            .map(|_| {
+                let db = db.clone();
+                tokio::spawn(async move { record_donation(&db, campaign_id, DONOR, 100, &"ab".repeat(32)).await })
+            })
+            .collect();
+
+        assert_eq!(successes(tasks).await, 1);
+        assert_eq!(raised(&db, campaign_id).await, (100, "completed".to_string()));
+    }
+
+    #[sqlx::test]
+    async fn a_donation_is_not_counted_again_in_another_hex_case(db: PgPool) {
+        let campaign_id = campaign(&db, 0, 1_000).await;
+
+        let donation = record_donation(&db, campaign_id, DONOR, 100, &"ab".repeat(32)).await.unwrap();
+        assert_eq!(donation.tx_hash, "ab".repeat(32));
+        assert!(matches!(
+            record_donation(&db, campaign_id, DONOR, 100, &"AB".repeat(32)).await,
+            Err(AppError::BadRequest(_))
+        ));
+        assert_eq!(raised(&db, campaign_id).await.0, 100);
+    }
+
+    #[sqlx::test]
+    async fn parallel_disbursements_never_overdraw(db: PgPool) {
+        let campaign_id = campaign(&db, 100, 1_000).await;
+        let chain: Arc<dyn ChainBackend> = Arc::new(MockChain::new());
+
+        let mut tasks = Vec::new();
+        for n in 0..5 {
+            let id = approved_disbursement(&db, campaign_id, 30).await;
+            let (db, chain) = (db.clone(), chain.clone());
+            tasks.push(tokio::spawn(async move {
+                execute_disbursement(&db, chain.as_ref(), id, &format!("payout-{}", n), ACTOR).await
+            }));
+        }
+
+        assert_eq!(successes(tasks).await, 3);
+        assert_eq!(raised(&db, campaign_id).await.0, 10);
+    }
+
+    #[sqlx::test]
+    async fn a_disbursement_executes_once(db: PgPool) {
+        let campaign_id = campaign(&db, 100, 1_000).await;
+        let id = approved_disbursement(&db, campaign_id, 40).await;
+        let chain: Arc<dyn ChainBackend> = Arc::new(MockChain::new());
+
+        let tasks = (0..5)
+            .map(|n| {
+                let (db, chain) = (db.clone(), chain.clone());
+                tokio::spawn(async move {
+                    execute_disbursement(&db, chain.as_ref(), id, &format!("payout-{}", n), ACTOR).await
+                })
+            })
+            .collect();
+
+        assert_eq!(successes(tasks).await, 1);
+        assert_eq!(raised(&db, campaign_id).await.0, 60);
+    }
+
+    #[sqlx::test]
+    async fn an_on_chain_payout_is_settled_after_the_chain_call(db: PgPool) {
+        let campaign_id = campaign(&db, 100, 1_000).await;
+        let chain = MockChain::new();
+        let contract_campaign_id = chain
+            .create_campaign(ORG, "Water", 1_000, 4_000_000_000)
+            .await
+            .unwrap() as u32;
+        chain.donate(contract_campaign_id, DONOR, 100).await.unwrap();
+        link(&db, campaign_id, contract_campaign_id).await;
+        let id = approved_disbursement(&db, campaign_id, 40).await;
+
+        let executed = execute_disbursement(&db, &chain, id, "unused", ACTOR).await.unwrap();
+
+        assert_eq!(executed.status, "executed");
+        assert!(chain.get_transaction(executed.tx_hash.as_deref().unwrap()).await.unwrap().is_some());
+        assert_eq!(raised(&db, campaign_id).await.0, 60);
+    }
+
+    #[sqlx::test]
+    async fn a_payout_rejected_before_submission_releases_the_reserved_funds(db: PgPool) {
+        let campaign_id = campaign(&db, 100, 1_000).await;
+        // The contract has no campaign 7, so the payout fails
+        link(&db, campaign_id, 7).await;
+        let id = approved_disbursement(&db, campaign_id, 40).await;
+
+        assert!(execute_disbursement(&db, &MockChain::new(), id, "unused", ACTOR).await.is_err());
+
+        assert_eq!(status(&db, id).await, "approved");
+        assert_eq!(raised(&db, campaign_id).await.0, 100);
+    }
+
+    #[sqlx::test]
+    async fn a_payout_that_times_out_after_submission_stays_executing(db: PgPool) {
+        let campaign_id = campaign(&db, 100, 1_000).await;
+        let chain = MockChain::new();
+        let contract_campaign_id = chain
+            .create_campaign(ORG, "Water", 1_000, 4_000_000_000)
+            .await
+            .unwrap() as u32;
+        chain.donate(contract_campaign_id, DONOR, 100).await.unwrap();
+        link(&db, campaign_id, contract_campaign_id).await;
+        let id = approved_disbursement(&db, campaign_id, 40).await;
+        chain.time_out_submissions();
+
+        let err = execute_disbursement(&db, &chain, id, "unused", ACTOR).await.unwrap_err();
+        assert!(matches!(err, AppError::Stellar(msg) if msg.contains("Timed out")));
+
+        // The payout landed, so the funds stay reserved and the row keeps its hash
+        let tx_hash = sqlx::query_scalar::<_, Option<String>>(
+            "SELECT tx_hash FROM disbursements WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_one(&db)
+        .await
+        .unwrap()
+        .unwrap();
+        assert!(chain.get_transaction(&tx_hash).await.unwrap().unwrap().successful);
+        assert_eq!(status(&db, id).await, "executing");
+        assert_eq!(raised(&db, campaign_id).await.0, 60);
+
+        // A retry cannot pay the organization twice
+        let retry = execute_disbursement(&db, &chain, id, "unused", ACTOR).await;
+        assert!(matches!(retry, Err(AppError::BadRequest(msg)) if msg.contains("already being executed")));
+        assert_eq!(chain.get_campaign(contract_campaign_id).await.unwrap()["raised"], "60");
+    }
+}
@@ -0,0 +1,527 @@
+use chrono::{DateTime, Utc};
+use data_encoding::BASE64URL_NOPAD;
+use serde::{Deserialize, Serialize};
+use sqlx::{Encode, Postgres, QueryBuilder, Type};
+use uuid::Uuid;
+
+use crate::{
+    error::AppError,
+    models::{
+        CampaignWithOrg, Disbursement, Donation, Organization, OrganizationMember, Page,
+        PlatformRoleGrant, ReconciliationDiscrepancy,
+    },
+};
+
+/// Largest page any list endpoint returns
+pub const MAX_PAGE_SIZE: i64 = 100;
+
+/// Most sort keys accepted in one `sort` parameter
+pub const MAX_SORT_KEYS: usize = 4;
+
+/// SQL type of a sortable column, so cursor values bind with the right type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    Int,
+    Text,
+    Time,
+    Uuid,
+}
+
+/// A field a listing can be sorted by: the API name and the column it orders
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortField {
+    pub name: &'static str,
+    pub column: &'static str,
+    pub kind: FieldKind,
+}
+
+impl SortField {
+    pub const fn new(name: &'static str, column: &'static str, kind: FieldKind) -> Self {
+        Self { name, column, kind }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortKey {
+    pub field: SortField,
+    pub descending: bool,
+}
+
+/// Sorting and paging rules of one list endpoint
+pub struct Listing {
+    pub fields: &'static [SortField],
+    pub default_sort: &'static str,
+    /// Unique columns appended to every sort so each row has one position
+    pub tiebreak: &'static [SortField],
+    pub default_limit: i64,
+}
+
+/// Value of a sort key in a cursor
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum CursorValue {
+    Int(i64),
+    Text(String),
+    Time(DateTime<Utc>),
+    Uuid(Uuid),
+}
+
+impl CursorValue {
+    fn kind(&self) -> FieldKind {
+        match self {
+            Self::Int(_) => FieldKind::Int,
+            Self::Text(_) => FieldKind::Text,
+            Self::Time(_) => FieldKind::Time,
+            Self::Uuid(_) => FieldKind::Uuid,
+        }
+    }
+
+    fn push_bind(&self, query: &mut QueryBuilder<'_, Postgres>) {
+        match self {
+            Self::Int(value) => query.push_bind(*value),
+            Self::Text(value) => query.push_bind(value.clone()),
+            Self::Time(value) => query.push_bind(*value),
+            Self::Uuid(value) => query.push_bind(*value),
+        };
+    }
+}
+
+/// Position after the last row of a page. Encoded as base64url JSON; the
+/// sort is included so a cursor cannot be replayed against another order.
+#[derive(Debug, Serialize, Deserialize)]
+struct Cursor {
+    sort: String,
+    values: Vec<CursorValue>,
+}
+
+/// Rows listed with keyset pagination expose the values they are sorted by
+pub trait Keyset {
+    /// Value of the sort field named `name`
+    fn sort_value(&self, name: &str) -> Option<CursorValue>;
+}
+
+/// One page of a listing: its sort, page size and the cursor to resume after
+#[derive(Debug)]
+pub struct PageRequest {
+    keys: Vec<SortKey>,
+    limit: i64,
+    after: Option<Vec<CursorValue>>,
+}
+
+impl Listing {
+    /// Resolve the `sort`, `cursor` and `limit` parameters of a request.
+    ///
+    /// Page sizes are clamped to `1..=MAX_PAGE_SIZE`.
+    pub fn page(
+        &self,
+        sort: Option<&str>,
+        cursor: Option<&str>,
+        limit: Option<i64>,
+    ) -> Result<PageRequest, AppError> {
+        let mut keys = parse_sort(sort, self.fields, self.default_sort)?;
+        keys.extend(self.tiebreak.iter().map(|field| SortKey {
+            field: *field,
+            descending: false,
+        }));
+        let limit = limit.unwrap_or(self.default_limit).clamp(1, MAX_PAGE_SIZE);
+
+        let after = match cursor.map(str::trim).filter(|cursor| !cursor.is_empty()) {
+            Some(cursor) => Some(decode_cursor(cursor, &keys)?),
+            None => None,
+        };
+
+        Ok(PageRequest { keys, limit, after })
+    }
+}
+
+impl PageRequest {
+    /// Append the keyset condition for rows after the cursor, the `ORDER BY`
+    /// and a `LIMIT` one past the page size to tell whether more rows follow.
+    ///
+    /// Must come after the query's `WHERE` conditions.
+    pub fn push_keyset(&self, query: &mut QueryBuilder<'_, Postgres>) {
+        if let Some(values) = &self.after {
+            // (k1 > v1) OR (k1 = v1 AND k2 > v2) OR ..., flipped for descending keys
+            query.push(" AND (");
+            for (i, key) in self.keys.iter().enumerate() {
+                if i > 0 {
+                    query.push(" OR ");
+                }
+                query.push("(");
+                for (earlier, value) in self.keys[..i].iter().zip(values) {
+                    query.push(earlier.field.column).push(" = ");
+                    value.push_bind(query);
+                    query.push(" AND ");
+                }
+                query
+                    .push(key.field.column)
+                    .push(if key.descending { " < " } else { " > " });
+                values[i].push_bind(query);
+                query.push(")");
+            }
+            query.push(")");
+        }
+
+        query.push(" ORDER BY ");
+        for (i, key) in self.keys.iter().enumerate() {
+            if i > 0 {
+                query.push(", ");
+            }
+            query
+                .push(key.field.column)
+                .push(if key.descending { " DESC" } else { " ASC" });
+        }
+        query.push(" LIMIT ").push_bind(self.limit + 1);
+    }
+
+    /// Cut the fetched rows down to one page and point at the next
+    pub fn finish<T: Keyset>(&self, mut rows: Vec<T>) -> Result<Page<T>, AppError> {
+        let has_more = rows.len() as i64 > self.limit;
+        rows.truncate(self.limit as usize);
+
+        let next_cursor = match rows.last() {
+            Some(last) if has_more => Some(self.encode_cursor(last)?),
+            _ => None,
+        };
+
+        Ok(Page {
+            items: rows,
+            next_cursor,
+            has_more,
+        })
+    }
+
+    fn encode_cursor<T: Keyset>(&self, row: &T) -> Result<String, AppError> {
+        let values = self
+            .keys
+            .iter()
+            .map(|key| {
+                row.sort_value(key.field.name).ok_or_else(|| {
+                    AppError::Internal(format!("Row has no sort value for {}", key.field.name))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let cursor = Cursor {
+            sort: sort_spec(&self.keys),
+            values,
+        };
+        let json = serde_json::to_vec(&cursor)
+            .map_err(|e| AppError::Internal(format!("Failed to encode cursor: {}", e)))?;
+
+        Ok(BASE64URL_NOPAD.encode(&json))
+    }
+}
+
+fn decode_cursor(cursor: &str, keys: &[SortKey]) -> Result<Vec<CursorValue>, AppError> {
+    let invalid = || AppError::BadRequest("Invalid cursor".to_string());
+    let json = BASE64URL_NOPAD.decode(cursor.as_bytes()).map_err(|_| invalid())?;
+    let cursor: Cursor = serde_json::from_slice(&json).map_err(|_| invalid())?;
+
+    if cursor.sort != sort_spec(keys) {
+        return Err(AppError::BadRequest(
+            "Cursor was issued for a different sort order".to_string(),
+        ));
+    }
+    let kinds_match = cursor.values.len() == keys.len()
+        && cursor
+            .values
+            .iter()
+            .zip(keys)
+            .all(|(value, key)| value.kind() == key.field.kind);
+    if !kinds_match {
+        return Err(invalid());
+    }
+
+    Ok(cursor.values)
+}
+
+fn sort_spec(keys: &[SortKey]) -> String {
+    keys.iter()
+        .map(|key| {
+            if key.descending {
+                format!("-{}", key.field.name)
+            } else {
+                key.field.name.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Parse a `sort` parameter such as `-raised_amount,deadline`.
+///
+/// Keys are comma-separated and a leading `-` sorts descending. Only names in
+/// `fields` are accepted, so the SQL that reaches the database comes from the
+/// whitelist rather than the request.
+pub fn parse_sort(
+    spec: Option<&str>,
+    fields: &[SortField],
+    default: &str,
+) -> Result<Vec<SortKey>, AppError> {
+    let spec = spec.map(str::trim).filter(|spec| !spec.is_empty()).unwrap_or(default);
+    let mut keys: Vec<SortKey> = Vec::new();
+
+    for part in spec.split(',').map(str::trim) {
+        let (name, descending) = match part.strip_prefix('-') {
+            Some(name) => (name, true),
+            None => (part, false),
+        };
+        let field = fields
+            .iter()
+            .find(|field| field.name == name)
+            .copied()
+            .ok_or_else(|| {
+                let allowed: Vec<&str> = fields.iter().map(|field| field.name).collect();
+                AppError::BadRequest(format!(
+                    "Cannot sort by '{}'; expected one of {}",
+                    name,
+                    allowed.join(", ")
+                ))
+            })?;
+        if keys.iter().any(|key| key.field == field) {
+            return Err(AppError::BadRequest(format!("Duplicate sort key '{}'", name)));
+        }
+        keys.push(SortKey { field, descending });
+    }
+
+    if keys.len() > MAX_SORT_KEYS {
+        return Err(AppError::BadRequest(format!(
+            "At most {} sort keys are allowed",
+            MAX_SORT_KEYS
+        )));
+    }
+
+    Ok(keys)
+}
+
+/// Append bound `column >= min` / `column <= max` conditions.
+///
+/// `field` names the range in the error returned when `min` exceeds `max`.
+pub fn push_range<'a, T>(
+    query: &mut QueryBuilder<'a, Postgres>,
+    field: &str,
+    column: &str,
+    min: Option<T>,
+    max: Option<T>,
+) -> Result<(), AppError>
+where
+    T: 'a + PartialOrd + Send + Encode<'a, Postgres> + Type<Postgres>,
+{
+    if let (Some(min), Some(max)) = (&min, &max) {
+        if min > max {
+            return Err(AppError::BadRequest(format!(
+                "Invalid {} range: minimum exceeds maximum",
+                field
+            )));
+        }
+    }
+    if let Some(min) = min {
+        query.push(" AND ").push(column).push(" >= ").push_bind(min);
+    }
+    if let Some(max) = max {
+        query.push(" AND ").push(column).push(" <= ").push_bind(max);
+    }
+    Ok(())
+}
+
+// Sort values of the listed models, by the field names their listings use
+
+impl Keyset for Organization {
+    fn sort_value(&self, name: &str) -> Option<CursorValue> {
+        match name {
+            "id" => Some(CursorValue::Uuid(self.id)),
+            "created_at" => Some(CursorValue::Time(self.created_at)),
+            _ => None,
+        }
+    }
+}
+
+impl Keyset for OrganizationMember {
+    fn sort_value(&self, name: &str) -> Option<CursorValue> {
+        match name {
+            "account" => Some(CursorValue::Text(self.account.clone())),
+            "created_at" => Some(CursorValue::Time(self.created_at)),
+            _ => None,
+        }
+    }
+}
+
+impl Keyset for CampaignWithOrg {
+    fn sort_value(&self, name: &str) -> Option<CursorValue> {
+        match name {
+            "id" => Some(CursorValue::Uuid(self.id)),
+            "created_at" => Some(CursorValue::Time(self.created_at)),
+            "deadline" => Some(CursorValue::Time(self.deadline)),
+            "goal_amount" => Some(CursorValue::Int(self.goal_amount)),
+            "raised_amount" => Some(CursorValue::Int(self.raised_amount)),
+            "name" => Some(CursorValue::Text(self.name.clone())),
+            _ => None,
+        }
+    }
+}
+
+impl Keyset for Donation {
+    fn sort_value(&self, name: &str) -> Option<CursorValue> {
+        match name {
+            "id" => Some(CursorValue::Uuid(self.id)),
+            "timestamp" => Some(CursorValue::Time(self.timestamp)),
+            "amount" => Some(CursorValue::Int(self.amount)),
+            _ => None,
+        }
+    }
+}
+
+impl Keyset for Disbursement {
+    fn sort_value(&self, name: &str) -> Option<CursorValue> {
+        match name {
+            "id" => Some(CursorValue::Uuid(self.id)),
+            "created_at" => Some(CursorValue::Time(self.created_at)),
+            _ => None,
+        }
+    }
+}
+
+impl Keyset for ReconciliationDiscrepancy {
+    fn sort_value(&self, name: &str) -> Option<CursorValue> {
+        match name {
+            "id" => Some(CursorValue::Uuid(self.id)),
+            "detected_at" => Some(CursorValue::Time(self.detected_at)),
+            _ => None,
+        }
+    }
+}
+
+impl Keyset for PlatformRoleGrant {
+    fn sort_value(&self, name: &str) -> Option<CursorValue> {
+        match name {
+            "account" => Some(CursorValue::Text(self.account.clone())),
+            "role" => Some(CursorValue::Text(self.role.clone())),
+            "created_at" => Some(CursorValue::Time(self.created_at)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LISTING: Listing = Listing {
+        fields: &[
+            SortField::new("created_at", "c.created_at", FieldKind::Time),
+            SortField::new("raised_amount", "c.raised_amount", FieldKind::Int),
+        ],
+        default_sort: "-created_at",
+        tiebreak: &[SortField::new("id", "c.id", FieldKind::Uuid)],
+        default_limit: 2,
+    };
+
+    struct Row {
+        id: Uuid,
+        raised_amount: i64,
+    }
+
+    impl Keyset for Row {
+        fn sort_value(&self, name: &str) -> Option<CursorValue> {
+            match name {
+                "id" => Some(CursorValue::Uuid(self.id)),
+                "raised_amount" => Some(CursorValue::Int(self.raised_amount)),
+                _ => None,
+            }
+        }
+    }
+
+    fn rows(amounts: &[i64]) -> Vec<Row> {
+        amounts
+            .iter()
+            .map(|amount| Row {
+                id: Uuid::new_v4(),
+                raised_amount: *amount,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sort_keys_come_from_the_whitelist() {
+        let keys = parse_sort(Some("-raised_amount, created_at"), LISTING.fields, "-created_at").unwrap();
+        assert_eq!(
+            keys.iter().map(|key| (key.field.column, key.descending)).collect::<Vec<_>>(),
+            vec![("c.raised_amount", true), ("c.created_at", false)]
+        );
+        assert_eq!(sort_spec(&parse_sort(Some(" "), LISTING.fields, "-created_at").unwrap()), "-created_at");
+        assert!(parse_sort(Some("created_at; DROP TABLE campaigns"), LISTING.fields, "created_at").is_err());
+        assert!(parse_sort(Some("created_at,-created_at"), LISTING.fields, "created_at").is_err());
+        assert!(parse_sort(Some("name"), LISTING.fields, "created_at").is_err());
+    }
+
+    #[test]
+    fn filters_bind_values_instead_of_splicing_them() {
+        let mut query = QueryBuilder::<Postgres>::new("SELECT * FROM campaigns c WHERE TRUE");
+        push_range(&mut query, "goal", "c.goal_amount", Some(10i64), None).unwrap();
+        push_range(&mut query, "raised", "c.raised_amount", Some(1i64), Some(5i64)).unwrap();
+        LISTING.page(None, None, None).unwrap().push_keyset(&mut query);
+
+        assert_eq!(
+            query.sql(),
+            "SELECT * FROM campaigns c WHERE TRUE AND c.goal_amount >= $1 \
+             AND c.raised_amount >= $2 AND c.raised_amount <= $3 \
+             ORDER BY c.created_at DESC, c.id ASC LIMIT $4"
+        );
+    }
+
+    #[test]
+    fn inverted_ranges_are_rejected() {
+        let mut query = QueryBuilder::<Postgres>::new("SELECT 1");
+        let result = push_range(&mut query, "amount", "amount", Some(5i64), Some(1i64));
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[test]
+    fn pages_resume_after_the_cursor() {
+        let first = LISTING.page(Some("-raised_amount"), None, None).unwrap();
+        let page = first.finish(rows(&[30, 20, 10])).unwrap();
+        assert!(page.has_more);
+        assert_eq!(page.items.len(), 2);
+
+        let cursor = page.next_cursor.unwrap();
+        let next = LISTING.page(Some("-raised_amount"), Some(&cursor), None).unwrap();
+        assert_eq!(
+            next.after,
+            Some(vec![CursorValue::Int(20), CursorValue::Uuid(page.items[1].id)])
+        );
+
+        let mut query = QueryBuilder::<Postgres>::new("SELECT * FROM campaigns c WHERE TRUE");
+        next.push_keyset(&mut query);
+        assert_eq!(
+            query.sql(),
+            "SELECT * FROM campaigns c WHERE TRUE AND ((c.raised_amount < $1) \
+             OR (c.raised_amount = $2 AND c.id > $3)) \
+             ORDER BY c.raised_amount DESC, c.id ASC LIMIT $4"
+        );
+
+        let last = next.finish(rows(&[10])).unwrap();
+        assert!(!last.has_more);
+        assert_eq!(last.next_cursor, None);
+    }
+
+    #[test]
+    fn cursors_are_checked_against_the_request() {
+        let page = LISTING.page(Some("-raised_amount"), None, None).unwrap();
+        let cursor = page.finish(rows(&[3, 2, 1])).unwrap().next_cursor.unwrap();
+
+        assert!(LISTING.page(Some("raised_amount"), Some(&cursor), None).is_err());
+        assert!(LISTING.page(None, Some("not-a-cursor"), None).is_err());
+
+        let forged = BASE64URL_NOPAD.encode(br#"{"sort":"-raised_amount,id","values":[{"Text":"x"},{"Text":"y"}]}"#);
+        assert!(matches!(
+            LISTING.page(Some("-raised_amount"), Some(&forged), None),
+            Err(AppError::BadRequest(_))
+        ));
+    }
+
+    #[test]
+    fn page_sizes_are_capped() {
+        assert_eq!(LISTING.page(None, None, None).unwrap().limit, 2);
+        assert_eq!(LISTING.page(None, None, Some(10_000)).unwrap().limit, MAX_PAGE_SIZE);
+        assert_eq!(LISTING.page(None, None, Some(0)).unwrap().limit, 1);
+    }
+}
@@ -0,0 +1,146 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde_json::json;
+
+use crate::{config::Config, error::AppError};
+
+/// Outgoing email, such as password reset tokens
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, to: &str, subject: &str, text: &str) -> Result<(), AppError>;
+}
+
+/// Mailer that posts each message as JSON (`from`, `to`, `subject`, `text`)
+/// to an email relay or provider API
+pub struct HttpMailer {
+    http: reqwest::Client,
+    url: String,
+    api_key: Option<String>,
+    from: String,
+}
+
+#[async_trait]
+impl Mailer for HttpMailer {
+    async fn send(&self, to: &str, subject: &str, text: &str) -> Result<(), AppError> {
+        let mut request = self.http.post(&self.url).json(&json!({
+            "from": &self.from,
+            "to": to,
+            "subject": subject,
+            "text": text,
+        }));
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        request
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| AppError::Internal(format!("Failed to send email: {}", e)))?;
+
+        Ok(())
+    }
+}
+
+/// Development stub that writes messages, secrets included, to the log.
+/// Only compiled into debug builds.
+#[cfg(debug_assertions)]
+pub struct LogMailer;
+
+#[cfg(debug_assertions)]
+#[async_trait]
+impl Mailer for LogMailer {
+    async fn send(&self, to: &str, subject: &str, text: &str) -> Result<(), AppError> {
+        tracing::info!("Email to {} ({}):\n{}", to, subject, text);
+        Ok(())
+    }
+}
+
+/// Drops every message; for deployments without email
+pub struct NoMailer;
+
+#[async_trait]
+impl Mailer for NoMailer {
+    async fn send(&self, _to: &str, subject: &str, _text: &str) -> Result<(), AppError> {
+        tracing::warn!("No mailer configured, dropped email: {}", subject);
+        Ok(())
+    }
+}
+
+/// Subject and body of a password reset email
+pub fn password_reset_email(token: &str, expiry_secs: u64) -> (String, String) {
+    (
+        "Reset your AidFlow password".to_string(),
+        format!(
+            "Use this token to choose a new AidFlow password within {} minutes:\n\n{}\n\n\
+             If you did not ask for a reset, you can ignore this email.",
+            expiry_secs.div_ceil(60),
+            token
+        ),
+    )
+}
+
+/// Build the mailer named by `MAILER`
+pub fn from_config(config: &Config) -> Result<Arc<dyn Mailer>, AppError> {
+    match config.mailer.as_str() {
+        "http" => {
+            let url = config.mailer_url.clone().ok_or_else(|| {
+                AppError::Internal("MAILER=http requires MAILER_URL".to_string())
+            })?;
+            Ok(Arc::new(HttpMailer {
+                http: reqwest::Client::new(),
+                url,
+                api_key: config.mailer_api_key.clone(),
+                from: config.mailer_from.clone(),
+            }))
+        }
+        #[cfg(debug_assertions)]
+        "log" => Ok(Arc::new(LogMailer)),
+        #[cfg(not(debug_assertions))]
+        "log" => Err(AppError::Internal(
+            "MAILER=log is only available in debug builds".to_string(),
+        )),
+        "none" => Ok(Arc::new(NoMailer)),
+        other => Err(AppError::Internal(format!("Unknown MAILER {}", other))),
+    }
+}
+
+#[cfg(test)]
+pub use recording::RecordingMailer;
+
+#[cfg(test)]
+mod recording {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Test stub that keeps every message as `(to, subject, text)`
+    #[derive(Default)]
+    pub struct RecordingMailer {
+        pub sent: Mutex<Vec<(String, String, String)>>,
+    }
+
+    #[async_trait]
+    impl Mailer for RecordingMailer {
+        async fn send(&self, to: &str, subject: &str, text: &str) -> Result<(), AppError> {
+            self.sent
+                .lock()
+                .unwrap()
+                .push((to.to_string(), subject.to_string(), text.to_string()));
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reset_emails_carry_the_token_and_its_lifetime() {
+        let (subject, text) = password_reset_email("f00d", 3600);
+        assert_eq!(subject, "Reset your AidFlow password");
+        assert!(text.contains("\n\nf00d\n\n"));
+        assert!(text.contains("within 60 minutes"));
+    }
+}
@@ -0,0 +1,366 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Mutex,
+};
+
+use async_trait::async_trait;
+use serde_json::json;
+
+use crate::{
+    error::AppError,
+    services::chain::{
+        decode_hash32, sequential_tx_hash, ChainBackend, ChainTransaction, ContractCall,
+        PreparedTransaction,
+    },
+};
+
+/// Contract address reported by [`MockChain`]
+pub const MOCK_CONTRACT_ID: &str = "CAAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQC526";
+
+/// Deterministic in-memory stand-in for the donation contract.
+///
+/// Campaign ids and transaction hashes are sequential, and the contract's
+/// basic checks (unknown or closed campaigns, non-positive amounts,
+/// overdrawn or disputed disbursements) are enforced so route tests see the
+/// same errors. As on the contract, a closed campaign can still pay out what
+/// it holds.
+#[derive(Default)]
+pub struct MockChain {
+    state: Mutex<MockState>,
+}
+
+#[derive(Default)]
+struct MockState {
+    campaigns: BTreeMap<u32, MockCampaign>,
+    transactions: HashMap<String, ChainTransaction>,
+    pending: HashMap<String, PendingDisbursement>,
+    tx_count: u64,
+    time_out_submissions: bool,
+}
+
+/// Disbursement prepared but not yet submitted
+struct PendingDisbursement {
+    campaign_id: u32,
+    amount: i128,
+    args: Vec<serde_json::Value>,
+}
+
+struct MockCampaign {
+    name: String,
+    org: String,
+    goal: i128,
+    raised: i128,
+    deadline: u64,
+    active: bool,
+    disputed: bool,
+    donations: Vec<serde_json::Value>,
+}
+
+impl MockChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Make later submissions apply their transaction and then report a
+    /// timeout, as an RPC node does when confirmation outlasts polling
+    #[cfg(test)]
+    pub fn time_out_submissions(&self) {
+        self.state.lock().unwrap().time_out_submissions = true;
+    }
+
+    /// Block payouts from a campaign, as an auditor's open dispute does
+    #[cfg(test)]
+    pub async fn open_dispute(&self, campaign_id: u32) -> Result<(), AppError> {
+        self.state.lock().unwrap().campaign_mut(campaign_id)?.disputed = true;
+        Ok(())
+    }
+}
+
+impl MockState {
+    /// Record a successful call and return its transaction hash
+    fn record(&mut self, function: &str, args: Vec<serde_json::Value>) -> String {
+        self.tx_count += 1;
+        let hash = sequential_tx_hash(self.tx_count);
+        self.transactions.insert(
+            hash.clone(),
+            ChainTransaction {
+                successful: true,
+                call: Some(ContractCall {
+                    contract_id: MOCK_CONTRACT_ID.to_string(),
+                    function: function.to_string(),
+                    args,
+                }),
+            },
+        );
+        hash
+    }
+
+    fn campaign_mut(&mut self, campaign_id: u32) -> Result<&mut MockCampaign, AppError> {
+        self.campaigns
+            .get_mut(&campaign_id)
+            .ok_or_else(|| AppError::Stellar("Campaign not found".to_string()))
+    }
+}
+
+#[async_trait]
+impl ChainBackend for MockChain {
+    fn contract_id(&self) -> String {
+        MOCK_CONTRACT_ID.to_string()
+    }
+
+    fn can_sign_for(&self, _address: &str) -> bool {
+        true
+    }
+
+    async fn create_campaign(
+        &self,
+        org_wallet: &str,
+        name: &str,
+        goal_amount: i64,
+        deadline: u64,
+    ) -> Result<i32, AppError> {
+        if goal_amount <= 0 {
+            return Err(AppError::Stellar("Goal must be greater than 0".to_string()));
+        }
+
+        let mut state = self.state.lock().unwrap();
+        let id = state.campaigns.len() as u32 + 1;
+        state.campaigns.insert(
+            id,
+            MockCampaign {
+                name: name.to_string(),
+                org: org_wallet.to_string(),
+                goal: goal_amount as i128,
+                raised: 0,
+                deadline,
+                active: true,
+                disputed: false,
+                donations: Vec::new(),
+            },
+        );
+        state.record(
+            "create_campaign",
+            vec![
+                json!(org_wallet),
+                json!(name),
+                json!(goal_amount.to_string()),
+                json!(deadline),
+            ],
+        );
+
+        Ok(id as i32)
+    }
+
+    async fn donate(
+        &self,
+        campaign_id: u32,
+        donor_address: &str,
+        amount: i64,
+    ) -> Result<String, AppError> {
+        if amount <= 0 {
+            return Err(AppError::Stellar(
+                "Donation amount must be greater than 0".to_string(),
+            ));
+        }
+
+        let mut state = self.state.lock().unwrap();
+        let campaign = state.campaign_mut(campaign_id)?;
+        if !campaign.active {
+            return Err(AppError::Stellar("Campaign is not active".to_string()));
+        }
+        campaign.raised += amount as i128;
+        campaign.donations.push(json!({
+            "donor": donor_address,
+            "amount": amount.to_string(),
+            "timestamp": 0,
+        }));
+
+        Ok(state.record(
+            "donate",
+            vec![
+                json!(campaign_id),
+                json!(donor_address),
+                json!(amount.to_string()),
+            ],
+        ))
+    }
+
+    async fn prepare_disbursement(
+        &self,
+        campaign_id: u32,
+        recipient_address: &str,
+        amount: i64,
+        purpose: &str,
+        evidence_hashes: &[String],
+    ) -> Result<PreparedTransaction, AppError> {
+        if amount <= 0 {
+            return Err(AppError::Stellar(
+                "Disbursement amount must be greater than 0".to_string(),
+            ));
+        }
+        if purpose.is_empty() || evidence_hashes.is_empty() {
+            return Err(AppError::Stellar(
+                "Purpose and at least one evidence hash are required".to_string(),
+            ));
+        }
+        for hash in evidence_hashes {
+            decode_hash32(hash)?;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        let campaign = state.campaign_mut(campaign_id)?;
+        if amount as i128 > campaign.raised {
+            return Err(AppError::Stellar("Insufficient funds in campaign".to_string()));
+        }
+        if campaign.disputed {
+            return Err(AppError::Stellar("Campaign is under dispute".to_string()));
+        }
+
+        state.tx_count += 1;
+        let tx_hash = sequential_tx_hash(state.tx_count);
+        state.pending.insert(
+            tx_hash.clone(),
+            PendingDisbursement {
+                campaign_id,
+                amount: amount as i128,
+                args: vec![
+                    json!(campaign_id),
+                    json!(recipient_address),
+                    json!(amount.to_string()),
+                    json!(purpose),
+                    json!(evidence_hashes),
+                ],
+            },
+        );
+
+        Ok(PreparedTransaction {
+            tx_hash,
+            payload: String::new(),
+        })
+    }
+
+    async fn submit(&self, prepared: &PreparedTransaction) -> Result<(), AppError> {
+        let mut state = self.state.lock().unwrap();
+        let pending = state.pending.remove(&prepared.tx_hash).ok_or_else(|| {
+            AppError::Stellar(format!("Transaction {} was not prepared", prepared.tx_hash))
+        })?;
+
+        // Funds or the dispute may have changed since the transaction was prepared
+        let campaign = state.campaign_mut(pending.campaign_id)?;
+        let successful = pending.amount <= campaign.raised && !campaign.disputed;
+        if successful {
+            campaign.raised -= pending.amount;
+        }
+        state.transactions.insert(
+            prepared.tx_hash.clone(),
+            ChainTransaction {
+                successful,
+                call: Some(ContractCall {
+                    contract_id: MOCK_CONTRACT_ID.to_string(),
+                    function: "disburse".to_string(),
+                    args: pending.args,
+                }),
+            },
+        );
+
+        if !successful {
+            return Err(AppError::Stellar(format!(
+                "Transaction {} finished with status FAILED",
+                prepared.tx_hash
+            )));
+        }
+        if state.time_out_submissions {
+            return Err(AppError::Stellar(format!(
+                "Timed out waiting for transaction {}",
+                prepared.tx_hash
+            )));
+        }
+        Ok(())
+    }
+
+    async fn close_campaign(&self, campaign_id: u32) -> Result<(), AppError> {
+        let mut state = self.state.lock().unwrap();
+        let campaign = state.campaign_mut(campaign_id)?;
+        if !campaign.active {
+            return Err(AppError::Stellar("Campaign already closed".to_string()));
+        }
+        campaign.active = false;
+        state.record("close_campaign", vec![json!(campaign_id)]);
+
+        Ok(())
+    }
+
+    async fn get_campaign(&self, campaign_id: u32) -> Result<serde_json::Value, AppError> {
+        let mut state = self.state.lock().unwrap();
+        let campaign = state.campaign_mut(campaign_id)?;
+
+        Ok(json!({
+            "id": campaign_id,
+            "name": campaign.name,
+            "org": campaign.org,
+            "goal": campaign.goal.to_string(),
+            "raised": campaign.raised.to_string(),
+            "deadline": campaign.deadline,
+            "active": campaign.active,
+            "parent": null,
+        }))
+    }
+
+    async fn get_donations(&self, campaign_id: u32) -> Result<Vec<serde_json::Value>, AppError> {
+        let state = self.state.lock().unwrap();
+        Ok(state
+            .campaigns
+            .get(&campaign_id)
+            .map(|campaign| campaign.donations.clone())
+            .unwrap_or_default())
+    }
+
+    async fn get_transaction(&self, tx_hash: &str) -> Result<Option<ChainTransaction>, AppError> {
+        Ok(self.state.lock().unwrap().transactions.get(tx_hash).cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORG: &str = "GAAZI4TCR3TY5OJHCTJC2A4QSY6CJWJH5IAJTGKIN2ER7LBNVKOCCWN7";
+    const RECIPIENT: &str = "GBRPYHIL2CI3FNQ4BXLFMNDLFJUNPU2HY3ZMFSHONUCEOASW7QC7OX2H";
+    const EVIDENCE: &str = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+
+    #[tokio::test]
+    async fn mock_chain_tracks_campaign_funds() {
+        let chain = MockChain::new();
+        let id = chain.create_campaign(ORG, "Water", 1_000, 2_000_000_000).await.unwrap() as u32;
+        assert_eq!(id, 1);
+
+        let donation_tx = chain.donate(id, RECIPIENT, 600).await.unwrap();
+        let disbursement_tx = chain
+            .disburse(id, RECIPIENT, 250, "Filters", &[EVIDENCE.to_string()])
+            .await
+            .unwrap();
+        assert_eq!(donation_tx, sequential_tx_hash(2));
+        assert_eq!(disbursement_tx, sequential_tx_hash(3));
+
+        let campaign = chain.get_campaign(id).await.unwrap();
+        assert_eq!(campaign["raised"], "350");
+        assert_eq!(campaign["active"], true);
+
+        chain.close_campaign(id).await.unwrap();
+        assert!(chain.donate(id, RECIPIENT, 1).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn mock_chain_rejects_overdrawn_disbursements() {
+        let chain = MockChain::new();
+        let id = chain.create_campaign(ORG, "Water", 1_000, 2_000_000_000).await.unwrap() as u32;
+        chain.donate(id, RECIPIENT, 100).await.unwrap();
+
+        let err = chain
+            .disburse(id, RECIPIENT, 101, "Filters", &[EVIDENCE.to_string()])
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::Stellar(msg) if msg == "Insufficient funds in campaign"));
+        assert!(chain.get_campaign(2).await.is_err());
+    }
+}
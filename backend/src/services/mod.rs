@@ -1,2 +1,19 @@
 // Service layer for business logic
+pub mod accounts;
+pub mod approvals;
+pub mod auth;
+pub mod chain;
+#[cfg(test)]
+pub mod in_process_chain;
+pub mod indexer;
+pub mod ledger;
+pub mod listing;
+pub mod mailer;
+pub mod mock_chain;
+pub mod reconciliation;
+pub mod roles;
+pub mod search;
+pub mod sep10;
+pub mod soroban_rpc;
 pub mod stellar_service;
+pub mod totp;
@@ -0,0 +1,480 @@
+use std::{sync::Arc, time::Duration};
+
+use serde_json::{json, Value};
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::{
+    error::AppError,
+    models::{Disbursement, ReconciliationReport},
+    services::ledger,
+    AppState,
+};
+
+/// Database view of a campaign
+#[derive(Debug, Clone, PartialEq)]
+pub struct DbSnapshot {
+    pub raised_amount: i64,
+    pub status: String,
+    pub donation_count: i64,
+    pub donation_total: i64,
+}
+
+/// Contract view of the same campaign
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChainSnapshot {
+    pub raised: i128,
+    pub active: bool,
+    pub donation_count: i64,
+    pub donation_total: i128,
+}
+
+/// A field on which the database and the contract disagree
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch {
+    pub field: &'static str,
+    pub db_value: String,
+    pub chain_value: String,
+}
+
+/// Run reconciliation periodically in the background
+pub fn spawn(state: Arc<AppState>) {
+    if state.config.reconciliation_interval_secs == 0 {
+        tracing::info!("Reconciliation disabled");
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(state.config.reconciliation_interval_secs));
+
+        loop {
+            interval.tick().await;
+            match reconcile(&state, state.config.reconciliation_auto_correct).await {
+                Ok(report)
+                    if report.discrepancies > 0
+                        || report.failed > 0
+                        || report.disbursements_settled > 0 =>
+                {
+                    tracing::warn!(
+                        "Reconciliation checked {} campaigns: {} discrepancies, {} corrected, {} failed, {} disbursements settled",
+                        report.campaigns_checked,
+                        report.discrepancies,
+                        report.corrected,
+                        report.failed,
+                        report.disbursements_settled
+                    )
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Reconciliation failed: {}", e),
+            }
+        }
+    });
+}
+
+/// Compare every on-chain campaign with the database and record what differs.
+///
+/// Discrepancies stay open until a later run finds the values in agreement
+/// again. With `auto_correct`, raised amounts and statuses are overwritten
+/// with the contract's values; donation history is only reported, since the
+/// indexer is what backfills missing donations.
+pub async fn reconcile(
+    state: &AppState,
+    auto_correct: bool,
+) -> Result<ReconciliationReport, AppError> {
+    let campaigns = sqlx::query_as::<_, (Uuid, i32)>(
+        "SELECT id, contract_campaign_id FROM campaigns WHERE contract_campaign_id IS NOT NULL ORDER BY created_at",
+    )
+    .fetch_all(&state.db)
+    .await?;
+
+    let mut report = ReconciliationReport::default();
+    for (campaign_id, contract_campaign_id) in campaigns {
+        report.campaigns_checked += 1;
+        match reconcile_campaign(state, campaign_id, contract_campaign_id as u32, auto_correct).await
+        {
+            Ok((found, corrected)) => {
+                report.discrepancies += found;
+                report.corrected += corrected;
+            }
+            Err(e) => {
+                tracing::warn!("Could not reconcile campaign {}: {}", campaign_id, e);
+                report.failed += 1;
+            }
+        }
+    }
+
+    report.disbursements_settled = settle_disbursements(state, &mut report).await?;
+
+    Ok(report)
+}
+
+/// Settle disbursements left `executing` after their payout was submitted
+/// but not confirmed. A successful transaction marks the disbursement
+/// `executed`; a failed one releases the reserved funds. Transactions the
+/// network does not know yet are left for a later run.
+async fn settle_disbursements(
+    state: &AppState,
+    report: &mut ReconciliationReport,
+) -> Result<usize, AppError> {
+    let disbursements = sqlx::query_as::<_, Disbursement>(
+        "SELECT * FROM disbursements WHERE status = 'executing' AND tx_hash IS NOT NULL ORDER BY created_at",
+    )
+    .fetch_all(&state.db)
+    .await?;
+
+    let mut settled = 0;
+    for disbursement in disbursements {
+        let tx_hash = disbursement.tx_hash.clone().unwrap_or_default();
+        let successful = match state.chain.get_transaction(&tx_hash).await {
+            Ok(Some(tx)) => tx.successful,
+            Ok(None) => continue,
+            Err(e) => {
+                tracing::warn!("Could not look up payout {}: {}", tx_hash, e);
+                report.failed += 1;
+                continue;
+            }
+        };
+
+        if !successful {
+            ledger::release_disbursement(&state.db, &disbursement).await?;
+            settled += 1;
+            continue;
+        }
+
+        let mut tx = state.db.begin().await?;
+        let executed = sqlx::query(
+            "UPDATE disbursements SET status = 'executed', executed_at = NOW() WHERE id = $1 AND status = 'executing'",
+        )
+        .bind(disbursement.id)
+        .execute(&mut *tx)
+        .await?;
+        if executed.rows_affected() == 0 {
+            continue;
+        }
+
+        // Log audit
+        sqlx::query(
+            r#"
+            INSERT INTO audit_logs (entity_type, entity_id, action, actor_address, details)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind("disbursement")
+        .bind(disbursement.id)
+        .bind("reconciled")
+        .bind(state.chain.contract_id())
+        .bind(json!({
+            "tx_hash": &tx_hash,
+            "amount": disbursement.amount
+        }))
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        settled += 1;
+    }
+
+    Ok(settled)
+}
+
+/// Reconcile one campaign, returning how many discrepancies were found and corrected
+async fn reconcile_campaign(
+    state: &AppState,
+    campaign_id: Uuid,
+    contract_campaign_id: u32,
+    auto_correct: bool,
+) -> Result<(usize, usize), AppError> {
+    let chain = chain_snapshot(
+        &state.chain.get_campaign(contract_campaign_id).await?,
+        &state.chain.get_donations(contract_campaign_id).await?,
+    )?;
+
+    let mut tx = state.db.begin().await?;
+
+    // Lock the campaign so the snapshot cannot move under a correction
+    let (raised_amount, status) = sqlx::query_as::<_, (Option<i64>, String)>(
+        "SELECT raised_amount, status FROM campaigns WHERE id = $1 FOR UPDATE",
+    )
+    .bind(campaign_id)
+    .fetch_one(&mut *tx)
+    .await?;
+    let (donation_count, donation_total) = sqlx::query_as::<_, (i64, i64)>(
+        "SELECT COUNT(*), COALESCE(SUM(amount), 0)::BIGINT FROM donations WHERE campaign_id = $1",
+    )
+    .bind(campaign_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let db = DbSnapshot {
+        raised_amount: raised_amount.unwrap_or(0),
+        status,
+        donation_count,
+        donation_total,
+    };
+    let mismatches = compare(&db, &chain);
+
+    // Anything no longer mismatching has been fixed elsewhere
+    let fields: Vec<&str> = mismatches.iter().map(|m| m.field).collect();
+    sqlx::query(
+        r#"
+        UPDATE reconciliation_discrepancies
+        SET resolved_at = NOW()
+        WHERE campaign_id = $1 AND resolved_at IS NULL AND NOT (field = ANY($2))
+        "#,
+    )
+    .bind(campaign_id)
+    .bind(&fields)
+    .execute(&mut *tx)
+    .await?;
+
+    // Donation history in sync means the raised amount difference is not indexer lag
+    let history_matches = !fields.contains(&"donation_count") && !fields.contains(&"donation_total");
+
+    let mut corrected = 0;
+    for mismatch in &mismatches {
+        let discrepancy_id = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            INSERT INTO reconciliation_discrepancies (campaign_id, field, db_value, chain_value)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (campaign_id, field) WHERE resolved_at IS NULL
+            DO UPDATE SET db_value = EXCLUDED.db_value,
+                          chain_value = EXCLUDED.chain_value,
+                          last_seen_at = NOW()
+            RETURNING id
+            "#,
+        )
+        .bind(campaign_id)
+        .bind(mismatch.field)
+        .bind(&mismatch.db_value)
+        .bind(&mismatch.chain_value)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if !auto_correct {
+            continue;
+        }
+        let applied = match mismatch.field {
+            "raised_amount" if history_matches => match i64::try_from(chain.raised) {
+                Ok(raised) => set_campaign_field(&mut tx, campaign_id, "raised_amount", raised).await?,
+                Err(_) => false,
+            },
+            "status" => {
+                let status = if chain.active { "active" } else { "closed" };
+                set_campaign_field(&mut tx, campaign_id, "status", status).await?
+            }
+            _ => false,
+        };
+        if !applied {
+            continue;
+        }
+
+        sqlx::query(
+            "UPDATE reconciliation_discrepancies SET corrected = true, resolved_at = NOW() WHERE id = $1",
+        )
+        .bind(discrepancy_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO audit_logs (entity_type, entity_id, action, actor_address, details)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind("campaign")
+        .bind(campaign_id)
+        .bind("reconciled")
+        .bind(state.chain.contract_id())
+        .bind(json!({
+            "field": mismatch.field,
+            "from": mismatch.db_value,
+            "to": mismatch.chain_value
+        }))
+        .execute(&mut *tx)
+        .await?;
+
+        corrected += 1;
+    }
+
+    tx.commit().await?;
+
+    Ok((mismatches.len(), corrected))
+}
+
+async fn set_campaign_field<T>(
+    conn: &mut PgConnection,
+    campaign_id: Uuid,
+    column: &str,
+    value: T,
+) -> Result<bool, AppError>
+where
+    T: for<'q> sqlx::Encode<'q, sqlx::Postgres> + sqlx::Type<sqlx::Postgres> + Send,
+{
+    // `column` is one of our own literals, never user input
+    let result = sqlx::query(&format!(
+        "UPDATE campaigns SET {} = $2, updated_at = NOW() WHERE id = $1",
+        column
+    ))
+    .bind(campaign_id)
+    .bind(value)
+    .execute(conn)
+    .await?;
+
+    Ok(result.rows_affected() == 1)
+}
+
+/// Read the contract's `get_campaign` and `get_donations` results
+pub fn chain_snapshot(campaign: &Value, donations: &[Value]) -> Result<ChainSnapshot, AppError> {
+    let invalid = |what: &str| AppError::Stellar(format!("Unexpected contract {}", what));
+
+    let amount = |value: &Value| -> Option<i128> {
+        match value {
+            Value::String(s) => s.parse().ok(),
+            Value::Number(n) => n.as_i64().map(i128::from),
+            _ => None,
+        }
+    };
+
+    let raised = amount(&campaign["raised"]).ok_or_else(|| invalid("campaign raised amount"))?;
+    let active = campaign["active"]
+        .as_bool()
+        .ok_or_else(|| invalid("campaign active flag"))?;
+    let donation_total = donations
+        .iter()
+        .map(|donation| amount(&donation["amount"]))
+        .sum::<Option<i128>>()
+        .ok_or_else(|| invalid("donation amount"))?;
+
+    Ok(ChainSnapshot {
+        raised,
+        active,
+        donation_count: donations.len() as i64,
+        donation_total,
+    })
+}
+
+/// Fields on which the database disagrees with the contract
+pub fn compare(db: &DbSnapshot, chain: &ChainSnapshot) -> Vec<Mismatch> {
+    let mut mismatches = Vec::new();
+    let mut check = |field, db_value: String, chain_value: String| {
+        if db_value != chain_value {
+            mismatches.push(Mismatch {
+                field,
+                db_value,
+                chain_value,
+            });
+        }
+    };
+
+    check(
+        "raised_amount",
+        db.raised_amount.to_string(),
+        chain.raised.to_string(),
+    );
+    // The contract only knows open or closed; completed and expired are
+    // database-side refinements that are compatible with either
+    let status_matches = match db.status.as_str() {
+        "active" => chain.active,
+        "closed" => !chain.active,
+        _ => true,
+    };
+    if !status_matches {
+        check(
+            "status",
+            db.status.clone(),
+            if chain.active { "active" } else { "closed" }.to_string(),
+        );
+    }
+    check(
+        "donation_count",
+        db.donation_count.to_string(),
+        chain.donation_count.to_string(),
+    );
+    check(
+        "donation_total",
+        db.donation_total.to_string(),
+        chain.donation_total.to_string(),
+    );
+
+    mismatches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn db(raised_amount: i64, status: &str, donation_count: i64, donation_total: i64) -> DbSnapshot {
+        DbSnapshot {
+            raised_amount,
+            status: status.to_string(),
+            donation_count,
+            donation_total,
+        }
+    }
+
+    #[test]
+    fn chain_snapshot_reads_contract_json() {
+        let campaign = json!({ "raised": "350", "active": true });
+        let donations = vec![
+            json!({ "donor": "GA", "amount": "500", "timestamp": 1 }),
+            json!({ "donor": "GB", "amount": "100", "timestamp": 2 }),
+        ];
+
+        let snapshot = chain_snapshot(&campaign, &donations).unwrap();
+        assert_eq!(
+            snapshot,
+            ChainSnapshot {
+                raised: 350,
+                active: true,
+                donation_count: 2,
+                donation_total: 600,
+            }
+        );
+        assert!(chain_snapshot(&json!({ "raised": "x", "active": true }), &[]).is_err());
+    }
+
+    #[test]
+    fn compare_reports_each_drifted_field() {
+        let chain = ChainSnapshot {
+            raised: 350,
+            active: false,
+            donation_count: 2,
+            donation_total: 600,
+        };
+
+        assert!(compare(&db(350, "closed", 2, 600), &chain).is_empty());
+        assert!(compare(&db(350, "expired", 2, 600), &chain).is_empty());
+
+        let mismatches = compare(&db(100, "active", 1, 100), &chain);
+        let fields: Vec<_> = mismatches.iter().map(|m| m.field).collect();
+        assert_eq!(
+            fields,
+            ["raised_amount", "status", "donation_count", "donation_total"]
+        );
+        assert_eq!(mismatches[0].db_value, "100");
+        assert_eq!(mismatches[0].chain_value, "350");
+        assert_eq!(mismatches[1].chain_value, "closed");
+    }
+
+    #[test]
+    fn compare_flags_closed_campaign_still_active_on_chain() {
+        let chain = ChainSnapshot {
+            raised: 0,
+            active: true,
+            donation_count: 0,
+            donation_total: 0,
+        };
+
+        let mismatches = compare(&db(0, "closed", 0, 0), &chain);
+        assert_eq!(
+            mismatches,
+            vec![Mismatch {
+                field: "status",
+                db_value: "closed".to_string(),
+                chain_value: "active".to_string(),
+            }]
+        );
+        assert!(compare(&db(0, "completed", 0, 0), &chain).is_empty());
+    }
+}
@@ -0,0 +1,263 @@
+use std::{collections::HashMap, sync::Arc};
+
+use axum::{async_trait, extract::FromRequestParts, http::request::Parts};
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{config::Config, error::AppError, services::auth::AuthUser, AppState};
+
+/// Platform-wide role stored in `platform_roles`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlatformRole {
+    Admin,
+    Auditor,
+}
+
+/// Role within one organization
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrgRole {
+    Member,
+    Owner,
+}
+
+impl PlatformRole {
+    pub fn parse(role: &str) -> Option<Self> {
+        match role {
+            "admin" => Some(Self::Admin),
+            "auditor" => Some(Self::Auditor),
+            _ => None,
+        }
+    }
+}
+
+impl OrgRole {
+    pub fn parse(role: &str) -> Option<Self> {
+        match role {
+            "owner" => Some(Self::Owner),
+            "member" => Some(Self::Member),
+            _ => None,
+        }
+    }
+}
+
+/// Authenticated caller together with everything they are allowed to act as.
+///
+/// Accounts without a platform role or membership are donors: they can use
+/// every public endpoint but none of the role-gated ones.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Principal {
+    pub account: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<Uuid>,
+    #[serde(skip_serializing)]
+    pub mfa_at: Option<u64>,
+    pub platform_roles: Vec<PlatformRole>,
+    pub org_roles: HashMap<Uuid, OrgRole>,
+}
+
+impl Principal {
+    /// Resolve the roles of a caller from the database and `PLATFORM_ADMINS`.
+    ///
+    /// Staff accounts contribute their organization link on top of whatever
+    /// their Stellar address holds.
+    pub async fn load(db: &PgPool, config: &Config, user: &AuthUser) -> Result<Self, AppError> {
+        let account = user.account.as_str();
+        let mut platform_roles: Vec<PlatformRole> =
+            sqlx::query_scalar::<_, String>("SELECT role FROM platform_roles WHERE account = $1")
+                .bind(account)
+                .fetch_all(db)
+                .await?
+                .iter()
+                .filter_map(|role| PlatformRole::parse(role))
+                .collect();
+        if config.platform_admins.iter().any(|admin| admin == account)
+            && !platform_roles.contains(&PlatformRole::Admin)
+        {
+            platform_roles.push(PlatformRole::Admin);
+        }
+
+        let memberships = sqlx::query_as::<_, (Uuid, String)>(
+            r#"
+            SELECT id, 'owner' FROM organizations WHERE wallet_address = $1
+            UNION ALL
+            SELECT org_id, role FROM organization_members WHERE account = $1
+            UNION ALL
+            SELECT org_id, org_role FROM users WHERE id = $2 AND org_id IS NOT NULL
+            "#,
+        )
+        .bind(account)
+        .bind(user.user_id)
+        .fetch_all(db)
+        .await?;
+
+        let mut org_roles = HashMap::new();
+        for (org_id, role) in memberships {
+            if let Some(role) = OrgRole::parse(&role) {
+                let entry = org_roles.entry(org_id).or_insert(role);
+                *entry = (*entry).max(role);
+            }
+        }
+
+        Ok(Self {
+            account: account.to_string(),
+            user_id: user.user_id,
+            mfa_at: user.mfa_at,
+            platform_roles,
+            org_roles,
+        })
+    }
+
+    pub fn is_admin(&self) -> bool {
+        self.platform_roles.contains(&PlatformRole::Admin)
+    }
+
+    pub fn is_auditor(&self) -> bool {
+        self.platform_roles.contains(&PlatformRole::Auditor)
+    }
+
+    pub fn org_role(&self, org_id: Uuid) -> Option<OrgRole> {
+        self.org_roles.get(&org_id).copied()
+    }
+
+    pub fn require_admin(&self) -> Result<(), AppError> {
+        if self.is_admin() {
+            Ok(())
+        } else {
+            Err(forbidden("Platform admin role required"))
+        }
+    }
+
+    /// Admins and auditors may read platform-wide reports
+    pub fn require_auditor(&self) -> Result<(), AppError> {
+        if self.is_admin() || self.is_auditor() {
+            Ok(())
+        } else {
+            Err(forbidden("Auditor role required"))
+        }
+    }
+
+    /// Owners and members of the organization
+    pub fn require_org_member(&self, org_id: Uuid) -> Result<(), AppError> {
+        if self.org_role(org_id).is_some() {
+            Ok(())
+        } else {
+            Err(forbidden("Organization membership required"))
+        }
+    }
+
+    pub fn require_org_owner(&self, org_id: Uuid) -> Result<(), AppError> {
+        if self.org_role(org_id) == Some(OrgRole::Owner) {
+            Ok(())
+        } else {
+            Err(forbidden("Organization owner role required"))
+        }
+    }
+
+    /// Organization owners, or a platform admin acting on their behalf
+    pub fn require_org_owner_or_admin(&self, org_id: Uuid) -> Result<(), AppError> {
+        if self.is_admin() {
+            Ok(())
+        } else {
+            self.require_org_owner(org_id)
+        }
+    }
+
+    /// Staff accounts must have passed a TOTP check within `max_age` seconds
+    /// of `now`. Wallet logins pass, as SEP-10 already proved the account key,
+    /// unless the wallet is linked to a staff account with TOTP enabled.
+    pub fn require_step_up(&self, now: u64, max_age: u64) -> Result<(), AppError> {
+        if self.user_id.is_none() {
+            return Ok(());
+        }
+        match self.mfa_at {
+            Some(at) if at <= now && now - at <= max_age => Ok(()),
+            _ => Err(forbidden("Two-factor step-up required")),
+        }
+    }
+}
+
+fn forbidden(message: &str) -> AppError {
+    AppError::Forbidden(message.to_string())
+}
+
+#[async_trait]
+impl FromRequestParts<Arc<AppState>> for Principal {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        let user = AuthUser::from_request_parts(parts, state).await?;
+        Principal::load(&state.db, &state.config, &user).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn principal(platform_roles: Vec<PlatformRole>, org_roles: &[(Uuid, OrgRole)]) -> Principal {
+        Principal {
+            account: "GA".to_string(),
+            user_id: None,
+            mfa_at: None,
+            platform_roles,
+            org_roles: org_roles.iter().copied().collect(),
+        }
+    }
+
+    #[test]
+    fn org_policies_follow_membership() {
+        let org = Uuid::new_v4();
+        let other = Uuid::new_v4();
+        let member = principal(vec![], &[(org, OrgRole::Member)]);
+        let owner = principal(vec![], &[(org, OrgRole::Owner)]);
+
+        assert!(member.require_org_member(org).is_ok());
+        assert!(member.require_org_owner(org).is_err());
+        assert!(member.require_org_member(other).is_err());
+        assert!(owner.require_org_member(org).is_ok());
+        assert!(owner.require_org_owner(org).is_ok());
+        assert!(owner.require_org_owner_or_admin(other).is_err());
+    }
+
+    #[test]
+    fn platform_roles_do_not_imply_membership() {
+        let org = Uuid::new_v4();
+        let admin = principal(vec![PlatformRole::Admin], &[]);
+        let auditor = principal(vec![PlatformRole::Auditor], &[]);
+        let donor = principal(vec![], &[]);
+
+        assert!(admin.require_admin().is_ok());
+        assert!(admin.require_auditor().is_ok());
+        assert!(admin.require_org_owner_or_admin(org).is_ok());
+        assert!(admin.require_org_member(org).is_err());
+
+        assert!(auditor.require_auditor().is_ok());
+        assert!(auditor.require_admin().is_err());
+
+        assert!(matches!(donor.require_auditor(), Err(AppError::Forbidden(_))));
+        assert!(donor.require_org_member(org).is_err());
+    }
+
+    #[test]
+    fn step_up_requires_a_recent_totp_check_for_staff() {
+        let now = 1_700_000_000;
+        let wallet = principal(vec![], &[]);
+        let staff = |mfa_at| Principal {
+            user_id: Some(Uuid::new_v4()),
+            mfa_at,
+            ..principal(vec![], &[])
+        };
+
+        assert!(wallet.require_step_up(now, 300).is_ok());
+        assert!(staff(Some(now - 300)).require_step_up(now, 300).is_ok());
+        assert!(staff(Some(now - 301)).require_step_up(now, 300).is_err());
+        assert!(staff(Some(now + 60)).require_step_up(now, 300).is_err());
+        assert!(matches!(staff(None).require_step_up(now, 300), Err(AppError::Forbidden(_))));
+    }
+}
@@ -0,0 +1,181 @@
+use sqlx::PgPool;
+
+use crate::{error::AppError, models::SearchResult};
+
+/// Markers `ts_headline` puts around matches; control characters cannot
+/// collide with text, so they survive escaping and become `<mark>` tags
+const START_SEL: char = '\u{2}';
+const STOP_SEL: char = '\u{3}';
+
+/// Longest query accepted, in characters
+pub const MAX_QUERY_LEN: usize = 200;
+
+/// `word_similarity` a name needs for a fuzzy match; pg_trgm's default of
+/// 0.6 misses single typos in short words
+const FUZZY_THRESHOLD: &str = "0.4";
+
+/// Which entities a search covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchScope {
+    All,
+    Campaigns,
+    Organizations,
+}
+
+impl SearchScope {
+    pub fn parse(kind: Option<&str>) -> Result<Self, AppError> {
+        match kind {
+            None | Some("") | Some("all") => Ok(Self::All),
+            Some("campaign") => Ok(Self::Campaigns),
+            Some("organization") => Ok(Self::Organizations),
+            Some(other) => Err(AppError::BadRequest(format!(
+                "Unknown search type '{}'; expected campaign or organization",
+                other
+            ))),
+        }
+    }
+
+    fn campaigns(self) -> bool {
+        self != Self::Organizations
+    }
+
+    fn organizations(self) -> bool {
+        self != Self::Campaigns
+    }
+}
+
+/// Rank campaigns and organizations matching `query` with full-text search
+pub async fn full_text(
+    db: &PgPool,
+    query: &str,
+    scope: SearchScope,
+    limit: i64,
+) -> Result<Vec<SearchResult>, AppError> {
+    let headline_options = format!(
+        "StartSel={}, StopSel={}, MaxWords=35, MinWords=15, MaxFragments=2",
+        START_SEL, STOP_SEL
+    );
+
+    let results = sqlx::query_as::<_, SearchResult>(
+        r#"
+        WITH q AS (SELECT websearch_to_tsquery('english', $1) AS tsq)
+        SELECT 'campaign' AS kind, c.id, c.name,
+               ts_headline('english', coalesce(c.description, c.name), q.tsq, $2) AS snippet,
+               ts_rank_cd(c.search_vector, q.tsq, 32) AS rank
+        FROM campaigns c, q
+        WHERE $3 AND c.search_vector @@ q.tsq
+        UNION ALL
+        SELECT 'organization' AS kind, o.id, o.name,
+               ts_headline('english', coalesce(o.description, o.name), q.tsq, $2) AS snippet,
+               ts_rank_cd(o.search_vector, q.tsq, 32) AS rank
+        FROM organizations o, q
+        WHERE $4 AND o.search_vector @@ q.tsq
+        ORDER BY rank DESC, name, id
+        LIMIT $5
+        "#,
+    )
+    .bind(query)
+    .bind(&headline_options)
+    .bind(scope.campaigns())
+    .bind(scope.organizations())
+    .bind(limit)
+    .fetch_all(db)
+    .await?;
+
+    Ok(results.into_iter().map(highlight).collect())
+}
+
+/// Typo-tolerant fallback: names sharing enough trigrams with a word of `query`
+pub async fn fuzzy(
+    db: &PgPool,
+    query: &str,
+    scope: SearchScope,
+    limit: i64,
+) -> Result<Vec<SearchResult>, AppError> {
+    // Set for this transaction only, so `<%` can still use the trigram indexes
+    let mut tx = db.begin().await?;
+    sqlx::query("SELECT set_config('pg_trgm.word_similarity_threshold', $1, true)")
+        .bind(FUZZY_THRESHOLD)
+        .execute(&mut *tx)
+        .await?;
+
+    let results = sqlx::query_as::<_, SearchResult>(
+        r#"
+        SELECT 'campaign' AS kind, c.id, c.name,
+               left(c.description, 200) AS snippet,
+               word_similarity($1, c.name) AS rank
+        FROM campaigns c
+        WHERE $2 AND $1 <% c.name
+        UNION ALL
+        SELECT 'organization' AS kind, o.id, o.name,
+               left(o.description, 200) AS snippet,
+               word_similarity($1, o.name) AS rank
+        FROM organizations o
+        WHERE $3 AND $1 <% o.name
+        ORDER BY rank DESC, name, id
+        LIMIT $4
+        "#,
+    )
+    .bind(query)
+    .bind(scope.campaigns())
+    .bind(scope.organizations())
+    .bind(limit)
+    .fetch_all(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    Ok(results.into_iter().map(highlight).collect())
+}
+
+/// HTML-escape a snippet and turn the headline markers into `<mark>` tags
+fn highlight(mut result: SearchResult) -> SearchResult {
+    result.snippet = result.snippet.map(|snippet| {
+        let mut html = String::with_capacity(snippet.len());
+        for c in snippet.chars() {
+            match c {
+                START_SEL => html.push_str("<mark>"),
+                STOP_SEL => html.push_str("</mark>"),
+                '&' => html.push_str("&amp;"),
+                '<' => html.push_str("&lt;"),
+                '>' => html.push_str("&gt;"),
+                '"' => html.push_str("&quot;"),
+                '\'' => html.push_str("&#39;"),
+                c => html.push(c),
+            }
+        }
+        html
+    });
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    #[test]
+    fn snippets_are_escaped_around_highlights() {
+        let result = highlight(SearchResult {
+            kind: "campaign".to_string(),
+            id: Uuid::nil(),
+            name: "Flood relief".to_string(),
+            snippet: Some("<b>Clean</b> \u{2}water\u{3} & food".to_string()),
+            rank: 0.5,
+        });
+
+        assert_eq!(
+            result.snippet.as_deref(),
+            Some("&lt;b&gt;Clean&lt;/b&gt; <mark>water</mark> &amp; food")
+        );
+    }
+
+    #[test]
+    fn scope_selects_entity_types() {
+        assert_eq!(SearchScope::parse(None).unwrap(), SearchScope::All);
+        let campaigns = SearchScope::parse(Some("campaign")).unwrap();
+        assert!(campaigns.campaigns() && !campaigns.organizations());
+        let organizations = SearchScope::parse(Some("organization")).unwrap();
+        assert!(!organizations.campaigns() && organizations.organizations());
+        assert!(SearchScope::parse(Some("donation")).is_err());
+    }
+}
@@ -0,0 +1,307 @@
+use ed25519_dalek::{Signature as Ed25519Signature, VerifyingKey};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use stellar_xdr::curr::{
+    DataValue, DecoratedSignature, Limits, ManageDataOp, Memo, MuxedAccount, Operation,
+    OperationBody, Preconditions, ReadXdr, SequenceNumber, String64, TimeBounds, TimePoint,
+    Transaction, TransactionEnvelope, TransactionExt, TransactionV1Envelope, Uint256, VecM,
+    WriteXdr,
+};
+
+use crate::{
+    error::AppError,
+    services::soroban_rpc::{xdr_error, StellarSigner},
+};
+
+/// How long a wallet has to sign and return a challenge
+pub const CHALLENGE_TTL_SECS: u64 = 300;
+
+/// Parameters shared by challenge creation and verification
+pub struct ChallengeContext<'a> {
+    pub home_domain: &'a str,
+    pub web_auth_domain: &'a str,
+    pub network_passphrase: &'a str,
+}
+
+impl ChallengeContext<'_> {
+    fn auth_data_name(&self) -> String {
+        format!("{} auth", self.home_domain)
+    }
+
+    fn network_id(&self) -> [u8; 32] {
+        Sha256::digest(self.network_passphrase.as_bytes()).into()
+    }
+}
+
+/// Build a SEP-10 challenge for `client_account`, signed by the server key.
+///
+/// The challenge is a sequence-0 transaction from the server account that can
+/// never be submitted. Its first operation is a `manage_data` sourced from the
+/// client with a random nonce, the second names the web auth domain.
+pub fn build_challenge(
+    server: &StellarSigner,
+    client_account: &str,
+    context: &ChallengeContext<'_>,
+    now: u64,
+) -> Result<String, AppError> {
+    let client = parse_account(client_account)
+        .ok_or_else(|| AppError::BadRequest(format!("Invalid Stellar account {}", client_account)))?;
+
+    let mut nonce = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut nonce);
+    let nonce: String = nonce.iter().map(|b| format!("{:02x}", b)).collect();
+
+    let operations = vec![
+        manage_data(client, &context.auth_data_name(), nonce.as_bytes())?,
+        manage_data(
+            server.public_key(),
+            "web_auth_domain",
+            context.web_auth_domain.as_bytes(),
+        )?,
+    ];
+
+    let tx = Transaction {
+        source_account: MuxedAccount::Ed25519(Uint256(server.public_key())),
+        fee: 100 * operations.len() as u32,
+        seq_num: SequenceNumber(0),
+        cond: Preconditions::Time(TimeBounds {
+            min_time: TimePoint(now),
+            max_time: TimePoint(now + CHALLENGE_TTL_SECS),
+        }),
+        memo: Memo::None,
+        operations: operations.try_into().map_err(xdr_error)?,
+        ext: TransactionExt::V0,
+    };
+
+    let mut envelope = TransactionV1Envelope {
+        tx,
+        signatures: VecM::default(),
+    };
+    let hash = envelope.hash(context.network_id()).map_err(xdr_error)?;
+    envelope.signatures = vec![server.sign(&hash)?].try_into().map_err(xdr_error)?;
+
+    TransactionEnvelope::Tx(envelope)
+        .to_xdr_base64(Limits::none())
+        .map_err(xdr_error)
+}
+
+/// Check a challenge returned by a wallet and return the account it proves.
+///
+/// The transaction must be one we issued (server source, sequence 0, server
+/// signature), still inside its time bounds, and signed by the client account
+/// named in the first operation. Any other signature is rejected.
+pub fn verify_challenge(
+    server_key: [u8; 32],
+    challenge_xdr: &str,
+    context: &ChallengeContext<'_>,
+    now: u64,
+) -> Result<String, AppError> {
+    let reject = |reason: &str| AppError::Unauthorized(format!("Invalid challenge: {}", reason));
+
+    let envelope = match TransactionEnvelope::from_xdr_base64(challenge_xdr, Limits::len(64 * 1024))
+    {
+        Ok(TransactionEnvelope::Tx(envelope)) => envelope,
+        Ok(_) => return Err(reject("unexpected envelope type")),
+        Err(_) => return Err(AppError::BadRequest("Malformed challenge transaction".to_string())),
+    };
+    let tx = &envelope.tx;
+
+    if tx.source_account != MuxedAccount::Ed25519(Uint256(server_key)) {
+        return Err(reject("not issued by this server"));
+    }
+    if tx.seq_num.0 != 0 {
+        return Err(reject("sequence number must be 0"));
+    }
+    match &tx.cond {
+        Preconditions::Time(bounds) if bounds.min_time.0 <= now && now <= bounds.max_time.0 => {}
+        Preconditions::Time(_) => return Err(reject("expired")),
+        _ => return Err(reject("missing time bounds")),
+    }
+
+    let mut client_key = None;
+    for (index, operation) in tx.operations.iter().enumerate() {
+        let (source, data) = match (&operation.source_account, &operation.body) {
+            (Some(MuxedAccount::Ed25519(Uint256(source))), OperationBody::ManageData(data)) => {
+                (*source, data)
+            }
+            _ => return Err(reject("operations must be sourced manage_data")),
+        };
+        let name = data.data_name.to_utf8_string_lossy();
+        let value = data.data_value.as_ref().map(|value| value.0.as_slice());
+
+        if index == 0 {
+            if name != context.auth_data_name() {
+                return Err(reject("wrong home domain"));
+            }
+            if value.map(<[u8]>::len) != Some(64) {
+                return Err(reject("nonce must be 64 bytes"));
+            }
+            client_key = Some(source);
+        } else if source != server_key {
+            return Err(reject("additional operations must be sourced from the server"));
+        } else if name == "web_auth_domain" && value != Some(context.web_auth_domain.as_bytes()) {
+            return Err(reject("wrong web auth domain"));
+        }
+    }
+    let client_key = client_key.ok_or_else(|| reject("no operations"))?;
+    if client_key == server_key {
+        return Err(reject("client account cannot be the server"));
+    }
+
+    let hash = envelope.hash(context.network_id()).map_err(xdr_error)?;
+    let signed_by = |key: [u8; 32]| {
+        envelope
+            .signatures
+            .iter()
+            .any(|signature| verify_signature(&key, &hash, signature))
+    };
+    if !signed_by(server_key) {
+        return Err(reject("missing server signature"));
+    }
+    if !signed_by(client_key) {
+        return Err(reject("missing client signature"));
+    }
+    if envelope.signatures.len() != 2 {
+        return Err(reject("unexpected signatures"));
+    }
+
+    Ok(stellar_strkey::ed25519::PublicKey(client_key).to_string())
+}
+
+fn parse_account(account: &str) -> Option<[u8; 32]> {
+    stellar_strkey::ed25519::PublicKey::from_string(account)
+        .ok()
+        .map(|key| key.0)
+}
+
+fn manage_data(source: [u8; 32], name: &str, value: &[u8]) -> Result<Operation, AppError> {
+    Ok(Operation {
+        source_account: Some(MuxedAccount::Ed25519(Uint256(source))),
+        body: OperationBody::ManageData(ManageDataOp {
+            data_name: String64(name.try_into().map_err(xdr_error)?),
+            data_value: Some(DataValue(value.to_vec().try_into().map_err(xdr_error)?)),
+        }),
+    })
+}
+
+fn verify_signature(key: &[u8; 32], hash: &[u8; 32], signature: &DecoratedSignature) -> bool {
+    if signature.hint.0 != key[28..] {
+        return false;
+    }
+    let (Ok(verifying_key), Ok(signature)) = (
+        VerifyingKey::from_bytes(key),
+        Ed25519Signature::from_slice(&signature.signature.0),
+    ) else {
+        return false;
+    };
+    verifying_key.verify_strict(hash, &signature).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PASSPHRASE: &str = "Test SDF Network ; September 2015";
+    const NOW: u64 = 1_700_000_000;
+
+    fn context() -> ChallengeContext<'static> {
+        ChallengeContext {
+            home_domain: "aidflow.example",
+            web_auth_domain: "api.aidflow.example",
+            network_passphrase: PASSPHRASE,
+        }
+    }
+
+    fn signer(seed: u8) -> StellarSigner {
+        StellarSigner::from_secret(&stellar_strkey::ed25519::PrivateKey([seed; 32]).to_string())
+            .unwrap()
+    }
+
+    fn client() -> StellarSigner {
+        signer(7)
+    }
+
+    fn client_account() -> String {
+        stellar_strkey::ed25519::PublicKey(client().public_key()).to_string()
+    }
+
+    /// Add `signer`'s signature to a challenge, as a wallet would
+    fn countersign(challenge: &str, signer: &StellarSigner) -> String {
+        let TransactionEnvelope::Tx(mut envelope) =
+            TransactionEnvelope::from_xdr_base64(challenge, Limits::none()).unwrap()
+        else {
+            unreachable!()
+        };
+        let hash = envelope.hash(context().network_id()).unwrap();
+        let mut signatures = envelope.signatures.to_vec();
+        signatures.push(signer.sign(&hash).unwrap());
+        envelope.signatures = signatures.try_into().unwrap();
+        TransactionEnvelope::Tx(envelope)
+            .to_xdr_base64(Limits::none())
+            .unwrap()
+    }
+
+    fn unauthorized(result: Result<String, AppError>) -> String {
+        match result {
+            Err(AppError::Unauthorized(msg)) => msg,
+            other => panic!("expected unauthorized, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn signed_challenge_proves_the_client_account() {
+        let server = signer(3);
+        let challenge = build_challenge(&server, &client_account(), &context(), NOW).unwrap();
+        let signed = countersign(&challenge, &client());
+
+        let account =
+            verify_challenge(server.public_key(), &signed, &context(), NOW + 10).unwrap();
+        assert_eq!(account, client_account());
+    }
+
+    #[test]
+    fn challenge_without_client_signature_is_rejected() {
+        let server = signer(3);
+        let challenge = build_challenge(&server, &client_account(), &context(), NOW).unwrap();
+
+        let msg = unauthorized(verify_challenge(server.public_key(), &challenge, &context(), NOW));
+        assert!(msg.contains("missing client signature"));
+
+        let signed = countersign(&challenge, &signer(9));
+        let msg = unauthorized(verify_challenge(server.public_key(), &signed, &context(), NOW));
+        assert!(msg.contains("missing client signature"));
+    }
+
+    #[test]
+    fn expired_or_foreign_challenges_are_rejected() {
+        let server = signer(3);
+        let challenge = build_challenge(&server, &client_account(), &context(), NOW).unwrap();
+        let signed = countersign(&challenge, &client());
+
+        let msg = unauthorized(verify_challenge(
+            server.public_key(),
+            &signed,
+            &context(),
+            NOW + CHALLENGE_TTL_SECS + 1,
+        ));
+        assert!(msg.contains("expired"));
+
+        let msg = unauthorized(verify_challenge([1; 32], &signed, &context(), NOW));
+        assert!(msg.contains("not issued by this server"));
+
+        let other_domain = ChallengeContext {
+            home_domain: "evil.example",
+            ..context()
+        };
+        let msg = unauthorized(verify_challenge(server.public_key(), &signed, &other_domain, NOW));
+        assert!(msg.contains("wrong home domain"));
+
+        let other_network = ChallengeContext {
+            network_passphrase: "Public Global Stellar Network ; September 2015",
+            ..context()
+        };
+        let msg =
+            unauthorized(verify_challenge(server.public_key(), &signed, &other_network, NOW));
+        assert!(msg.contains("missing server signature"));
+    }
+}
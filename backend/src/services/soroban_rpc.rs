@@ -0,0 +1,912 @@
+use std::{str::FromStr, time::Duration};
+
+use data_encoding::HEXLOWER;
+use ed25519_dalek::{Signer, SigningKey};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use stellar_xdr::curr::{
+    AccountId, DecoratedSignature, FeeBumpTransactionInnerTx, HostFunction, InvokeContractArgs, InvokeHostFunctionOp,
+    LedgerEntryData, LedgerKey, LedgerKeyAccount, Limits, Memo, MuxedAccount, Operation,
+    OperationBody, Preconditions, PublicKey, ReadXdr, ScAddress, ScSymbol, ScVal,
+    SequenceNumber, Signature, SignatureHint, SorobanAuthorizationEntry, SorobanCredentials,
+    SorobanTransactionData, Transaction, TransactionEnvelope, TransactionExt, TransactionMeta,
+    TransactionV1Envelope, Uint256, VecM, WriteXdr,
+};
+
+use crate::{config::Config, error::AppError};
+
+/// Inclusion fee added on top of the resource fee returned by simulation
+const BASE_FEE: u32 = 100;
+
+/// JSON-RPC client for a Soroban RPC server
+pub struct SorobanRpcClient {
+    http: reqwest::Client,
+    rpc_url: String,
+    network_passphrase: String,
+    poll_interval: Duration,
+    max_poll_attempts: u32,
+}
+
+/// Transaction that has been signed but not yet sent
+pub struct SignedTransaction {
+    /// Lowercase hex hash the network will know the transaction by
+    pub hash: String,
+    pub envelope: TransactionEnvelope,
+}
+
+/// Ed25519 key that signs transactions and pays their fees
+pub struct StellarSigner {
+    key: SigningKey,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GetLedgerEntriesResult {
+    #[serde(default)]
+    entries: Vec<LedgerEntryResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LedgerEntryResult {
+    xdr: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulateTransactionResult {
+    pub error: Option<String>,
+    pub transaction_data: Option<String>,
+    pub min_resource_fee: Option<String>,
+    #[serde(default)]
+    pub results: Vec<SimulateHostFunctionResult>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SimulateHostFunctionResult {
+    #[serde(default)]
+    pub auth: Vec<String>,
+    pub xdr: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SendTransactionResult {
+    pub status: String,
+    pub hash: String,
+    pub error_result_xdr: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetTransactionResult {
+    pub status: String,
+    pub result_meta_xdr: Option<String>,
+    pub envelope_xdr: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetHealthResult {
+    pub latest_ledger: u32,
+    pub oldest_ledger: u32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetEventsResult {
+    #[serde(default)]
+    pub events: Vec<ContractEventResult>,
+    /// Resume point after the last event returned (or scanned)
+    pub cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractEventResult {
+    pub id: String,
+    pub ledger: u32,
+    pub contract_id: String,
+    pub tx_hash: String,
+    /// Base64 `ScVal` topics
+    pub topic: Vec<String>,
+    /// Base64 `ScVal` body
+    pub value: String,
+    #[serde(default = "default_true")]
+    pub in_successful_contract_call: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Where `getEvents` should start reading
+pub enum EventsStart<'a> {
+    Ledger(u32),
+    Cursor(&'a str),
+}
+
+/// Contract function called by the first operation of a transaction
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvokedContract {
+    pub contract_id: String,
+    pub function: String,
+    pub args: Vec<ScVal>,
+}
+
+impl StellarSigner {
+    /// Parse a Stellar secret seed (`S...`)
+    pub fn from_secret(secret: &str) -> Result<Self, AppError> {
+        let seed = stellar_strkey::ed25519::PrivateKey::from_string(secret)
+            .map_err(|_| AppError::Stellar("Invalid Stellar secret key".to_string()))?;
+        Ok(Self {
+            key: SigningKey::from_bytes(&seed.0),
+        })
+    }
+
+    pub fn public_key(&self) -> [u8; 32] {
+        self.key.verifying_key().to_bytes()
+    }
+
+    pub fn account_id(&self) -> AccountId {
+        AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(self.public_key())))
+    }
+
+    pub(crate) fn sign(&self, payload: &[u8; 32]) -> Result<DecoratedSignature, AppError> {
+        let public_key = self.public_key();
+        let signature = self.key.sign(payload).to_bytes();
+        Ok(DecoratedSignature {
+            hint: SignatureHint([public_key[28], public_key[29], public_key[30], public_key[31]]),
+            signature: Signature(signature.try_into().map_err(xdr_error)?),
+        })
+    }
+}
+
+impl SorobanRpcClient {
+    pub fn new(rpc_url: &str, network_passphrase: &str) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            rpc_url: rpc_url.to_string(),
+            network_passphrase: network_passphrase.to_string(),
+            poll_interval: Duration::from_secs(1),
+            max_poll_attempts: 30,
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(&config.soroban_rpc_url, &config.network_passphrase)
+    }
+
+    /// Override how often and how long `getTransaction` is polled after submission
+    #[cfg(test)]
+    pub fn with_polling(mut self, interval: Duration, max_attempts: u32) -> Self {
+        self.poll_interval = interval;
+        self.max_poll_attempts = max_attempts;
+        self
+    }
+
+    fn network_id(&self) -> [u8; 32] {
+        Sha256::digest(self.network_passphrase.as_bytes()).into()
+    }
+
+    async fn request<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<T, AppError> {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+
+        let response: serde_json::Value = self
+            .http
+            .post(&self.rpc_url)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| AppError::Stellar(format!("RPC {} failed: {}", method, e)))?
+            .json()
+            .await
+            .map_err(|e| AppError::Stellar(format!("RPC {} returned invalid JSON: {}", method, e)))?;
+
+        if let Some(error) = response.get("error") {
+            return Err(AppError::Stellar(format!("RPC {} error: {}", method, error)));
+        }
+
+        let result = response
+            .get("result")
+            .cloned()
+            .ok_or_else(|| AppError::Stellar(format!("RPC {} returned no result", method)))?;
+
+        serde_json::from_value(result)
+            .map_err(|e| AppError::Stellar(format!("RPC {} returned unexpected result: {}", method, e)))
+    }
+
+    /// Fetch the current sequence number of an account via `getLedgerEntries`
+    pub async fn get_account_sequence(&self, account_id: &AccountId) -> Result<i64, AppError> {
+        let key = LedgerKey::Account(LedgerKeyAccount {
+            account_id: account_id.clone(),
+        });
+        let result: GetLedgerEntriesResult = self
+            .request(
+                "getLedgerEntries",
+                json!({ "keys": [key.to_xdr_base64(Limits::none()).map_err(xdr_error)?] }),
+            )
+            .await?;
+
+        let entry = result
+            .entries
+            .first()
+            .ok_or_else(|| AppError::Stellar(format!("Account {} not found", account_id)))?;
+
+        match LedgerEntryData::from_xdr_base64(&entry.xdr, Limits::none()).map_err(xdr_error)? {
+            LedgerEntryData::Account(account) => Ok(account.seq_num.0),
+            _ => Err(AppError::Stellar("Ledger entry is not an account".to_string())),
+        }
+    }
+
+    pub async fn simulate_transaction(
+        &self,
+        envelope: &TransactionEnvelope,
+    ) -> Result<SimulateTransactionResult, AppError> {
+        let result: SimulateTransactionResult = self
+            .request(
+                "simulateTransaction",
+                json!({ "transaction": envelope.to_xdr_base64(Limits::none()).map_err(xdr_error)? }),
+            )
+            .await?;
+
+        if let Some(error) = &result.error {
+            return Err(AppError::Stellar(format!("Simulation failed: {}", error)));
+        }
+
+        Ok(result)
+    }
+
+    pub async fn send_transaction(
+        &self,
+        envelope: &TransactionEnvelope,
+    ) -> Result<SendTransactionResult, AppError> {
+        self.request(
+            "sendTransaction",
+            json!({ "transaction": envelope.to_xdr_base64(Limits::none()).map_err(xdr_error)? }),
+        )
+        .await
+    }
+
+    pub async fn get_transaction(&self, hash: &str) -> Result<GetTransactionResult, AppError> {
+        self.request("getTransaction", json!({ "hash": hash })).await
+    }
+
+    /// Ledger range the server currently retains
+    pub async fn get_health(&self) -> Result<GetHealthResult, AppError> {
+        self.request("getHealth", json!({})).await
+    }
+
+    /// Fetch up to `limit` events emitted by `contract_id`
+    pub async fn get_events(
+        &self,
+        contract_id: &str,
+        start: EventsStart<'_>,
+        limit: u32,
+    ) -> Result<GetEventsResult, AppError> {
+        let filters = json!([{ "type": "contract", "contractIds": [contract_id] }]);
+        let params = match start {
+            EventsStart::Ledger(ledger) => json!({
+                "startLedger": ledger,
+                "filters": filters,
+                "pagination": { "limit": limit }
+            }),
+            EventsStart::Cursor(cursor) => json!({
+                "filters": filters,
+                "pagination": { "cursor": cursor, "limit": limit }
+            }),
+        };
+        self.request("getEvents", params).await
+    }
+
+    /// Simulate a read-only contract call and return its result without submitting
+    pub async fn simulate_contract_call(
+        &self,
+        source: &AccountId,
+        contract_id: &str,
+        function: &str,
+        args: Vec<ScVal>,
+    ) -> Result<ScVal, AppError> {
+        let tx = build_invoke_transaction(source, 0, contract_id, function, args)?;
+        let simulation = self.simulate_transaction(&unsigned_envelope(tx)).await?;
+        let result = simulation
+            .results
+            .first()
+            .ok_or_else(|| AppError::Stellar("Simulation returned no result".to_string()))?;
+
+        ScVal::from_xdr_base64(&result.xdr, Limits::none()).map_err(xdr_error)
+    }
+
+    /// Invoke a contract function: build the transaction, simulate it, attach the
+    /// footprint, auth and fees, sign, submit and wait for the result.
+    ///
+    /// Returns the transaction hash and the function's return value.
+    pub async fn invoke_contract(
+        &self,
+        signer: &StellarSigner,
+        contract_id: &str,
+        function: &str,
+        args: Vec<ScVal>,
+    ) -> Result<(String, ScVal), AppError> {
+        let signed = self.prepare_invocation(signer, contract_id, function, args).await?;
+        let return_value = self.submit_transaction(&signed).await?;
+        Ok((signed.hash, return_value))
+    }
+
+    /// Build, simulate, assemble and sign a contract invocation without sending it.
+    ///
+    /// Nothing reaches the network's ledger, so an error here means the call
+    /// was never made.
+    pub async fn prepare_invocation(
+        &self,
+        signer: &StellarSigner,
+        contract_id: &str,
+        function: &str,
+        args: Vec<ScVal>,
+    ) -> Result<SignedTransaction, AppError> {
+        let source = signer.account_id();
+        let sequence = self.get_account_sequence(&source).await? + 1;
+        let tx = build_invoke_transaction(&source, sequence, contract_id, function, args)?;
+
+        let simulation = self.simulate_transaction(&unsigned_envelope(tx.clone())).await?;
+        let tx = assemble_transaction(tx, &simulation)?;
+
+        let mut envelope = TransactionV1Envelope {
+            tx,
+            signatures: VecM::default(),
+        };
+        let hash = envelope.hash(self.network_id()).map_err(xdr_error)?;
+        envelope.signatures = vec![signer.sign(&hash)?].try_into().map_err(xdr_error)?;
+
+        Ok(SignedTransaction {
+            hash: HEXLOWER.encode(&hash),
+            envelope: TransactionEnvelope::Tx(envelope),
+        })
+    }
+
+    /// Send a signed transaction and wait for its return value.
+    ///
+    /// An error does not mean the transaction failed: once sent it may still
+    /// be applied, so callers must look it up by hash before retrying.
+    pub async fn submit_transaction(&self, signed: &SignedTransaction) -> Result<ScVal, AppError> {
+        let sent = self.send_transaction(&signed.envelope).await?;
+        match sent.status.as_str() {
+            "PENDING" | "DUPLICATE" => {}
+            status => {
+                return Err(AppError::Stellar(format!(
+                    "Transaction {} rejected with status {}{}",
+                    sent.hash,
+                    status,
+                    sent.error_result_xdr
+                        .map(|xdr| format!(": {}", xdr))
+                        .unwrap_or_default()
+                )))
+            }
+        }
+
+        self.wait_for_transaction(&sent.hash).await
+    }
+
+    /// Poll `getTransaction` until the transaction leaves `NOT_FOUND`
+    async fn wait_for_transaction(&self, hash: &str) -> Result<ScVal, AppError> {
+        for _ in 0..self.max_poll_attempts {
+            let tx = self.get_transaction(hash).await?;
+            match tx.status.as_str() {
+                "SUCCESS" => {
+                    return match tx.result_meta_xdr {
+                        Some(meta) => return_value_from_meta(&meta),
+                        None => Ok(ScVal::Void),
+                    }
+                }
+                "NOT_FOUND" => tokio::time::sleep(self.poll_interval).await,
+                status => {
+                    return Err(AppError::Stellar(format!(
+                        "Transaction {} finished with status {}",
+                        hash, status
+                    )))
+                }
+            }
+        }
+
+        Err(AppError::Stellar(format!(
+            "Timed out waiting for transaction {}",
+            hash
+        )))
+    }
+}
+
+pub(crate) fn xdr_error(e: stellar_xdr::curr::Error) -> AppError {
+    AppError::Stellar(format!("XDR error: {}", e))
+}
+
+fn build_invoke_transaction(
+    source: &AccountId,
+    sequence: i64,
+    contract_id: &str,
+    function: &str,
+    args: Vec<ScVal>,
+) -> Result<Transaction, AppError> {
+    let contract_address = ScAddress::from_str(contract_id)
+        .map_err(|_| AppError::Stellar(format!("Invalid contract id {}", contract_id)))?;
+    let PublicKey::PublicKeyTypeEd25519(source_key) = &source.0;
+
+    let operation = Operation {
+        source_account: None,
+        body: OperationBody::InvokeHostFunction(InvokeHostFunctionOp {
+            host_function: HostFunction::InvokeContract(InvokeContractArgs {
+                contract_address,
+                function_name: ScSymbol(function.try_into().map_err(xdr_error)?),
+                args: args.try_into().map_err(xdr_error)?,
+            }),
+            auth: VecM::default(),
+        }),
+    };
+
+    Ok(Transaction {
+        source_account: MuxedAccount::Ed25519(source_key.clone()),
+        fee: BASE_FEE,
+        seq_num: SequenceNumber(sequence),
+        cond: Preconditions::None,
+        memo: Memo::None,
+        operations: vec![operation].try_into().map_err(xdr_error)?,
+        ext: TransactionExt::V0,
+    })
+}
+
+fn unsigned_envelope(tx: Transaction) -> TransactionEnvelope {
+    TransactionEnvelope::Tx(TransactionV1Envelope {
+        tx,
+        signatures: VecM::default(),
+    })
+}
+
+/// Apply the footprint, resource fee and authorization entries from simulation
+fn assemble_transaction(
+    mut tx: Transaction,
+    simulation: &SimulateTransactionResult,
+) -> Result<Transaction, AppError> {
+    let transaction_data = simulation
+        .transaction_data
+        .as_ref()
+        .ok_or_else(|| AppError::Stellar("Simulation returned no transaction data".to_string()))?;
+    let soroban_data =
+        SorobanTransactionData::from_xdr_base64(transaction_data, Limits::none()).map_err(xdr_error)?;
+
+    let min_resource_fee: u32 = simulation
+        .min_resource_fee
+        .as_deref()
+        .unwrap_or("0")
+        .parse()
+        .map_err(|_| AppError::Stellar("Simulation returned an invalid resource fee".to_string()))?;
+
+    let auth = simulation
+        .results
+        .first()
+        .map(|result| {
+            result
+                .auth
+                .iter()
+                .map(|entry| SorobanAuthorizationEntry::from_xdr_base64(entry, Limits::none()))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .transpose()
+        .map_err(xdr_error)?
+        .unwrap_or_default();
+
+    // Only the source account signs; other addresses would need their own signatures
+    if let Some(entry) = auth
+        .iter()
+        .find(|entry| matches!(entry.credentials, SorobanCredentials::Address(_)))
+    {
+        if let SorobanCredentials::Address(credentials) = &entry.credentials {
+            return Err(AppError::Stellar(format!(
+                "Transaction requires authorization from {}",
+                credentials.address
+            )));
+        }
+    }
+
+    let mut operations: Vec<Operation> = tx.operations.into();
+    if let Some(Operation {
+        body: OperationBody::InvokeHostFunction(op),
+        ..
+    }) = operations.first_mut()
+    {
+        op.auth = auth.try_into().map_err(xdr_error)?;
+    }
+    tx.operations = operations.try_into().map_err(xdr_error)?;
+    tx.fee = BASE_FEE
+        .checked_add(min_resource_fee)
+        .ok_or_else(|| AppError::Stellar("Transaction fee overflow".to_string()))?;
+    tx.ext = TransactionExt::V1(soroban_data);
+
+    Ok(tx)
+}
+
+/// Extract the contract invocation from a transaction envelope, if it has one
+pub fn invoked_contract_from_envelope(envelope_xdr: &str) -> Result<Option<InvokedContract>, AppError> {
+    let operations = match TransactionEnvelope::from_xdr_base64(envelope_xdr, Limits::none())
+        .map_err(xdr_error)?
+    {
+        TransactionEnvelope::Tx(envelope) => envelope.tx.operations,
+        TransactionEnvelope::TxFeeBump(envelope) => match envelope.tx.inner_tx {
+            FeeBumpTransactionInnerTx::Tx(inner) => inner.tx.operations,
+        },
+        TransactionEnvelope::TxV0(_) => return Ok(None),
+    };
+
+    Ok(operations.first().and_then(|op| match &op.body {
+        OperationBody::InvokeHostFunction(InvokeHostFunctionOp {
+            host_function: HostFunction::InvokeContract(invoke),
+            ..
+        }) => Some(InvokedContract {
+            contract_id: invoke.contract_address.to_string(),
+            function: invoke.function_name.0.to_utf8_string_lossy(),
+            args: invoke.args.to_vec(),
+        }),
+        _ => None,
+    }))
+}
+
+fn return_value_from_meta(meta_xdr: &str) -> Result<ScVal, AppError> {
+    match TransactionMeta::from_xdr_base64(meta_xdr, Limits::none()).map_err(xdr_error)? {
+        TransactionMeta::V3(meta) => Ok(meta
+            .soroban_meta
+            .map(|soroban| soroban.return_value)
+            .unwrap_or(ScVal::Void)),
+        TransactionMeta::V4(meta) => Ok(meta
+            .soroban_meta
+            .and_then(|soroban| soroban.return_value)
+            .unwrap_or(ScVal::Void)),
+        _ => Ok(ScVal::Void),
+    }
+}
+
+/// Convert a contract value into JSON, keeping 128-bit integers as strings
+pub fn scval_to_json(value: &ScVal) -> serde_json::Value {
+    match value {
+        ScVal::Bool(b) => json!(b),
+        ScVal::Void => serde_json::Value::Null,
+        ScVal::U32(n) => json!(n),
+        ScVal::I32(n) => json!(n),
+        ScVal::U64(n) => json!(n),
+        ScVal::I64(n) => json!(n),
+        ScVal::Timepoint(t) => json!(t.0),
+        ScVal::Duration(d) => json!(d.0),
+        ScVal::U128(_) => json!(u128::try_from(value.clone()).unwrap_or_default().to_string()),
+        ScVal::I128(_) => json!(i128::try_from(value.clone()).unwrap_or_default().to_string()),
+        ScVal::String(s) => json!(s.to_utf8_string_lossy()),
+        ScVal::Symbol(s) => json!(s.to_utf8_string_lossy()),
+        ScVal::Bytes(bytes) => json!(bytes.iter().map(|b| format!("{:02x}", b)).collect::<String>()),
+        ScVal::Address(address) => json!(address.to_string()),
+        ScVal::Vec(Some(items)) => items.iter().map(scval_to_json).collect(),
+        ScVal::Map(Some(map)) => serde_json::Value::Object(
+            map.iter()
+                .map(|entry| {
+                    let key = match scval_to_json(&entry.key) {
+                        serde_json::Value::String(s) => s,
+                        other => other.to_string(),
+                    };
+                    (key, scval_to_json(&entry.val))
+                })
+                .collect(),
+        ),
+        other => json!(format!("{:?}", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{extract::State, routing::post, Json, Router};
+    use ed25519_dalek::{Verifier, VerifyingKey};
+    use std::sync::{Arc, Mutex};
+    use stellar_xdr::curr::{
+        AccountEntry, AccountEntryExt, ExtensionPoint, LedgerEntryChanges, LedgerFootprint, ScMap, ScMapEntry,
+        ScString, SorobanResources, SorobanTransactionDataExt, SorobanTransactionMeta,
+        SorobanTransactionMetaExt, String32, Thresholds, TransactionMetaV3,
+    };
+
+    const CONTRACT_ID: &str = "CAAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQC526";
+    const PASSPHRASE: &str = "Standalone Network ; February 2017";
+
+    #[derive(Default)]
+    struct MockRpc {
+        requests: Vec<serde_json::Value>,
+        pending_polls: u32,
+        simulation_error: Option<String>,
+        final_status: Option<String>,
+    }
+
+    type SharedMock = Arc<Mutex<MockRpc>>;
+
+    fn signer() -> StellarSigner {
+        let secret = stellar_strkey::ed25519::PrivateKey([7u8; 32]).to_string();
+        StellarSigner::from_secret(&secret).unwrap()
+    }
+
+    fn account_entry_xdr(account_id: AccountId, sequence: i64) -> String {
+        LedgerEntryData::Account(AccountEntry {
+            account_id,
+            balance: 10_000_000_000,
+            seq_num: SequenceNumber(sequence),
+            num_sub_entries: 0,
+            inflation_dest: None,
+            flags: 0,
+            home_domain: String32::default(),
+            thresholds: Thresholds([1, 0, 0, 0]),
+            signers: VecM::default(),
+            ext: AccountEntryExt::V0,
+        })
+        .to_xdr_base64(Limits::none())
+        .unwrap()
+    }
+
+    fn transaction_data_xdr() -> String {
+        SorobanTransactionData {
+            ext: SorobanTransactionDataExt::V0,
+            resources: SorobanResources {
+                footprint: LedgerFootprint {
+                    read_only: VecM::default(),
+                    read_write: VecM::default(),
+                },
+                instructions: 1_000_000,
+                disk_read_bytes: 1_000,
+                write_bytes: 1_000,
+            },
+            resource_fee: 50_000,
+        }
+        .to_xdr_base64(Limits::none())
+        .unwrap()
+    }
+
+    fn result_meta_xdr(return_value: ScVal) -> String {
+        TransactionMeta::V3(TransactionMetaV3 {
+            ext: ExtensionPoint::V0,
+            tx_changes_before: LedgerEntryChanges::default(),
+            operations: VecM::default(),
+            tx_changes_after: LedgerEntryChanges::default(),
+            soroban_meta: Some(SorobanTransactionMeta {
+                ext: SorobanTransactionMetaExt::V0,
+                events: VecM::default(),
+                return_value,
+                diagnostic_events: VecM::default(),
+            }),
+        })
+        .to_xdr_base64(Limits::none())
+        .unwrap()
+    }
+
+    async fn handle_rpc(
+        State(mock): State<SharedMock>,
+        Json(request): Json<serde_json::Value>,
+    ) -> Json<serde_json::Value> {
+        let mut mock = mock.lock().unwrap();
+        mock.requests.push(request.clone());
+
+        let result = match request["method"].as_str().unwrap() {
+            "getLedgerEntries" => json!({
+                "entries": [{ "xdr": account_entry_xdr(signer().account_id(), 41) }],
+                "latestLedger": 100
+            }),
+            "simulateTransaction" => match &mock.simulation_error {
+                Some(error) => json!({ "error": error, "latestLedger": 100 }),
+                None => json!({
+                    "transactionData": transaction_data_xdr(),
+                    "minResourceFee": "50000",
+                    "results": [{
+                        "auth": [],
+                        "xdr": ScVal::U32(7).to_xdr_base64(Limits::none()).unwrap()
+                    }],
+                    "latestLedger": 100
+                }),
+            },
+            "sendTransaction" => json!({
+                "status": "PENDING",
+                "hash": "abc123",
+                "latestLedger": 100
+            }),
+            "getTransaction" if mock.pending_polls > 0 => {
+                mock.pending_polls -= 1;
+                json!({ "status": "NOT_FOUND", "latestLedger": 100 })
+            }
+            "getTransaction" => match &mock.final_status {
+                Some(status) => json!({ "status": status, "latestLedger": 101 }),
+                None => json!({
+                    "status": "SUCCESS",
+                    "latestLedger": 101,
+                    "resultMetaXdr": result_meta_xdr(ScVal::U32(7))
+                }),
+            },
+            method => panic!("unexpected RPC method {}", method),
+        };
+
+        Json(json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }))
+    }
+
+    async fn spawn_mock_rpc(mock: MockRpc) -> (SorobanRpcClient, SharedMock) {
+        let mock = Arc::new(Mutex::new(mock));
+        let app = Router::new()
+            .route("/", post(handle_rpc))
+            .with_state(mock.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = SorobanRpcClient::new(&url, PASSPHRASE).with_polling(Duration::from_millis(1), 5);
+        (client, mock)
+    }
+
+    fn sent_envelope(mock: &SharedMock) -> TransactionV1Envelope {
+        let mock = mock.lock().unwrap();
+        let request = mock
+            .requests
+            .iter()
+            .find(|r| r["method"] == "sendTransaction")
+            .unwrap();
+        let xdr = request["params"]["transaction"].as_str().unwrap();
+        match TransactionEnvelope::from_xdr_base64(xdr, Limits::none()).unwrap() {
+            TransactionEnvelope::Tx(envelope) => envelope,
+            _ => panic!("expected a v1 transaction envelope"),
+        }
+    }
+
+    #[tokio::test]
+    async fn invoke_contract_simulates_signs_and_polls() {
+        let (client, mock) = spawn_mock_rpc(MockRpc {
+            pending_polls: 2,
+            ..Default::default()
+        })
+        .await;
+        let signer = signer();
+
+        let (hash, value) = client
+            .invoke_contract(&signer, CONTRACT_ID, "close_campaign", vec![ScVal::U32(1)])
+            .await
+            .unwrap();
+
+        assert_eq!(value, ScVal::U32(7));
+
+        let methods: Vec<String> = mock
+            .lock()
+            .unwrap()
+            .requests
+            .iter()
+            .map(|r| r["method"].as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(
+            methods,
+            [
+                "getLedgerEntries",
+                "simulateTransaction",
+                "sendTransaction",
+                "getTransaction",
+                "getTransaction",
+                "getTransaction"
+            ]
+        );
+
+        let envelope = sent_envelope(&mock);
+        assert_eq!(envelope.tx.seq_num, SequenceNumber(42));
+        assert_eq!(envelope.tx.fee, BASE_FEE + 50_000);
+        assert!(matches!(envelope.tx.ext, TransactionExt::V1(_)));
+
+        let network_id: [u8; 32] = Sha256::digest(PASSPHRASE.as_bytes()).into();
+        let envelope_hash = envelope.hash(network_id).unwrap();
+        assert_eq!(hash, HEXLOWER.encode(&envelope_hash));
+        let signature = ed25519_dalek::Signature::from_slice(&envelope.signatures[0].signature.0).unwrap();
+        VerifyingKey::from_bytes(&signer.public_key())
+            .unwrap()
+            .verify(&envelope_hash, &signature)
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn invoke_contract_surfaces_simulation_errors() {
+        let (client, mock) = spawn_mock_rpc(MockRpc {
+            simulation_error: Some("HostError: Error(Contract, #1)".to_string()),
+            ..Default::default()
+        })
+        .await;
+
+        let err = client
+            .invoke_contract(&signer(), CONTRACT_ID, "donate", vec![])
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, AppError::Stellar(msg) if msg.contains("Error(Contract, #1)")));
+        assert!(!mock
+            .lock()
+            .unwrap()
+            .requests
+            .iter()
+            .any(|r| r["method"] == "sendTransaction"));
+    }
+
+    #[tokio::test]
+    async fn invoke_contract_reports_failed_transactions() {
+        let (client, _) = spawn_mock_rpc(MockRpc {
+            final_status: Some("FAILED".to_string()),
+            ..Default::default()
+        })
+        .await;
+
+        let err = client
+            .invoke_contract(&signer(), CONTRACT_ID, "close_campaign", vec![ScVal::U32(1)])
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, AppError::Stellar(msg) if msg.contains("FAILED")));
+    }
+
+    #[tokio::test]
+    async fn simulate_contract_call_returns_result_without_submitting() {
+        let (client, mock) = spawn_mock_rpc(MockRpc::default()).await;
+
+        let value = client
+            .simulate_contract_call(&signer().account_id(), CONTRACT_ID, "get_campaigns_count", vec![])
+            .await
+            .unwrap();
+
+        assert_eq!(value, ScVal::U32(7));
+        assert_eq!(mock.lock().unwrap().requests.len(), 1);
+    }
+
+    #[test]
+    fn invoked_contract_is_read_from_envelope() {
+        let tx = build_invoke_transaction(
+            &signer().account_id(),
+            1,
+            CONTRACT_ID,
+            "donate",
+            vec![ScVal::U32(3), ScVal::from(250i128)],
+        )
+        .unwrap();
+        let xdr = unsigned_envelope(tx).to_xdr_base64(Limits::none()).unwrap();
+
+        assert_eq!(
+            invoked_contract_from_envelope(&xdr).unwrap(),
+            Some(InvokedContract {
+                contract_id: CONTRACT_ID.to_string(),
+                function: "donate".to_string(),
+                args: vec![ScVal::U32(3), ScVal::from(250i128)],
+            })
+        );
+    }
+
+    #[test]
+    fn scval_to_json_converts_contract_structs() {
+        let campaign = ScVal::Map(Some(ScMap(
+            vec![
+                ScMapEntry {
+                    key: ScVal::Symbol(ScSymbol("active".try_into().unwrap())),
+                    val: ScVal::Bool(true),
+                },
+                ScMapEntry {
+                    key: ScVal::Symbol(ScSymbol("name".try_into().unwrap())),
+                    val: ScVal::String(ScString("Water".try_into().unwrap())),
+                },
+                ScMapEntry {
+                    key: ScVal::Symbol(ScSymbol("raised".try_into().unwrap())),
+                    val: ScVal::from(1_000i128),
+                },
+            ]
+            .try_into()
+            .unwrap(),
+        )));
+
+        assert_eq!(
+            scval_to_json(&campaign),
+            json!({ "active": true, "name": "Water", "raised": "1000" })
+        );
+    }
+}
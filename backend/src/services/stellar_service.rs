@@ -1,4 +1,105 @@
-use crate::{config::Config, error::AppError};
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use stellar_xdr::curr::{
+    Limits, ReadXdr, ScAddress, ScBytes, ScString, ScVal, ScVec, TransactionEnvelope, WriteXdr,
+};
+
+use crate::{
+    config::Config,
+    error::AppError,
+    services::{
+        chain::{decode_hash32, ChainBackend, ChainTransaction, ContractCall, PreparedTransaction},
+        soroban_rpc::{
+            invoked_contract_from_envelope, scval_to_json, xdr_error, SignedTransaction,
+            SorobanRpcClient, StellarSigner,
+        },
+    },
+};
+
+/// Chain backend that talks to the deployed contract over Soroban RPC
+pub struct RpcChain {
+    config: Config,
+}
+
+impl RpcChain {
+    pub fn new(config: Config) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait]
+impl ChainBackend for RpcChain {
+    fn contract_id(&self) -> String {
+        self.config.contract_id.clone()
+    }
+
+    /// Only the `STELLAR_SECRET_KEY` account; contract calls needing another
+    /// account's authorization would fail simulation
+    fn can_sign_for(&self, address: &str) -> bool {
+        signer(&self.config).is_ok_and(|signer| {
+            stellar_strkey::ed25519::PublicKey(signer.public_key()).to_string() == address
+        })
+    }
+
+    async fn create_campaign(
+        &self,
+        org_wallet: &str,
+        name: &str,
+        goal_amount: i64,
+        deadline: u64,
+    ) -> Result<i32, AppError> {
+        create_campaign_on_chain(&self.config, org_wallet, name, goal_amount, deadline).await
+    }
+
+    async fn donate(
+        &self,
+        campaign_id: u32,
+        donor_address: &str,
+        amount: i64,
+    ) -> Result<String, AppError> {
+        process_donation_on_chain(&self.config, campaign_id, donor_address, amount).await
+    }
+
+    async fn prepare_disbursement(
+        &self,
+        campaign_id: u32,
+        recipient_address: &str,
+        amount: i64,
+        purpose: &str,
+        evidence_hashes: &[String],
+    ) -> Result<PreparedTransaction, AppError> {
+        prepare_disbursement_on_chain(
+            &self.config,
+            campaign_id,
+            recipient_address,
+            amount,
+            purpose,
+            evidence_hashes,
+        )
+        .await
+    }
+
+    async fn submit(&self, prepared: &PreparedTransaction) -> Result<(), AppError> {
+        submit_on_chain(&self.config, prepared).await
+    }
+
+    async fn close_campaign(&self, campaign_id: u32) -> Result<(), AppError> {
+        close_campaign_on_chain(&self.config, campaign_id).await
+    }
+
+    async fn get_campaign(&self, campaign_id: u32) -> Result<serde_json::Value, AppError> {
+        get_campaign_from_chain(&self.config, campaign_id).await
+    }
+
+    async fn get_donations(&self, campaign_id: u32) -> Result<Vec<serde_json::Value>, AppError> {
+        get_donations_from_chain(&self.config, campaign_id).await
+    }
+
+    async fn get_transaction(&self, tx_hash: &str) -> Result<Option<ChainTransaction>, AppError> {
+        get_transaction_from_chain(&self.config, tx_hash).await
+    }
+}
 
 /// Create a campaign on the Stellar blockchain
 pub async fn create_campaign_on_chain(
@@ -8,9 +109,6 @@ pub async fn create_campaign_on_chain(
     goal_amount: i64,
     deadline: u64,
 ) -> Result<i32, AppError> {
-    // In production, this would use stellar-sdk to invoke the smart contract
-    // For now, return a mock contract campaign ID
-    
     tracing::info!(
         "Creating campaign on-chain: org={}, name={}, goal={}, deadline={}",
         org_wallet,
@@ -19,23 +117,34 @@ pub async fn create_campaign_on_chain(
         deadline
     );
 
-    // Mock implementation - in production, call:
-    // stellar_sdk::invoke_contract(
-    //     &config.contract_id,
-    //     "create_campaign",
-    //     vec![org_wallet, name, goal_amount, deadline]
-    // )
+    let (_, value) = invoke(
+        config,
+        "create_campaign",
+        vec![
+            address_arg(org_wallet)?,
+            string_arg(name)?,
+            ScVal::from(goal_amount as i128),
+            ScVal::from(deadline),
+        ],
+    )
+    .await?;
 
-    Ok(1) // Return mock campaign ID
+    match value {
+        ScVal::U32(id) => Ok(id as i32),
+        other => Err(AppError::Stellar(format!(
+            "create_campaign returned unexpected value {:?}",
+            other
+        ))),
+    }
 }
 
-/// Process a donation on the Stellar blockchain
+/// Process a donation on the Stellar blockchain, returning the transaction hash
 pub async fn process_donation_on_chain(
     config: &Config,
     campaign_id: u32,
     donor_address: &str,
     amount: i64,
-) -> Result<(), AppError> {
+) -> Result<String, AppError> {
     tracing::info!(
         "Processing donation on-chain: campaign_id={}, donor={}, amount={}",
         campaign_id,
@@ -43,36 +152,75 @@ pub async fn process_donation_on_chain(
         amount
     );
 
-    // Mock implementation - in production, call:
-    // stellar_sdk::invoke_contract(
-    //     &config.contract_id,
-    //     "donate",
-    //     vec![campaign_id, donor_address, amount]
-    // )
+    let (tx_hash, _) = invoke(
+        config,
+        "donate",
+        vec![
+            ScVal::from(campaign_id),
+            address_arg(donor_address)?,
+            ScVal::from(amount as i128),
+        ],
+    )
+    .await?;
 
-    Ok(())
+    Ok(tx_hash)
 }
 
-/// Execute a disbursement on the Stellar blockchain
-pub async fn execute_disbursement_on_chain(
+/// Build and sign a disbursement for the Stellar blockchain without sending it
+pub async fn prepare_disbursement_on_chain(
     config: &Config,
     campaign_id: u32,
     recipient_address: &str,
     amount: i64,
-) -> Result<(), AppError> {
+    purpose: &str,
+    evidence_hashes: &[String],
+) -> Result<PreparedTransaction, AppError> {
     tracing::info!(
-        "Executing disbursement on-chain: campaign_id={}, recipient={}, amount={}",
+        "Preparing disbursement on-chain: campaign_id={}, recipient={}, amount={}",
         campaign_id,
         recipient_address,
         amount
     );
 
-    // Mock implementation - in production, call:
-    // stellar_sdk::invoke_contract(
-    //     &config.contract_id,
-    //     "disburse",
-    //     vec![campaign_id, recipient_address, amount]
-    // )
+    let evidence = evidence_hashes
+        .iter()
+        .map(|hash| hash32_arg(hash))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let signer = signer(config)?;
+    let signed = SorobanRpcClient::from_config(config)
+        .prepare_invocation(
+            &signer,
+            &config.contract_id,
+            "disburse",
+            vec![
+                ScVal::from(campaign_id),
+                address_arg(recipient_address)?,
+                ScVal::from(amount as i128),
+                string_arg(purpose)?,
+                ScVal::Vec(Some(ScVec(evidence.try_into().map_err(xdr_error)?))),
+            ],
+        )
+        .await?;
+
+    Ok(PreparedTransaction {
+        tx_hash: signed.hash,
+        payload: signed.envelope.to_xdr_base64(Limits::none()).map_err(xdr_error)?,
+    })
+}
+
+/// Send a transaction prepared by this backend and wait for it
+pub async fn submit_on_chain(config: &Config, prepared: &PreparedTransaction) -> Result<(), AppError> {
+    tracing::info!("Submitting transaction on-chain: tx_hash={}", prepared.tx_hash);
+
+    let envelope =
+        TransactionEnvelope::from_xdr_base64(&prepared.payload, Limits::none()).map_err(xdr_error)?;
+    SorobanRpcClient::from_config(config)
+        .submit_transaction(&SignedTransaction {
+            hash: prepared.tx_hash.clone(),
+            envelope,
+        })
+        .await?;
 
     Ok(())
 }
@@ -84,16 +232,71 @@ pub async fn get_campaign_from_chain(
 ) -> Result<serde_json::Value, AppError> {
     tracing::info!("Fetching campaign from chain: campaign_id={}", campaign_id);
 
-    // Mock implementation - in production, call:
-    // stellar_sdk::invoke_contract(
-    //     &config.contract_id,
-    //     "get_campaign",
-    //     vec![campaign_id]
-    // )
+    let signer = signer(config)?;
+    let value = SorobanRpcClient::from_config(config)
+        .simulate_contract_call(
+            &signer.account_id(),
+            &config.contract_id,
+            "get_campaign",
+            vec![ScVal::from(campaign_id)],
+        )
+        .await?;
+
+    Ok(scval_to_json(&value))
+}
+
+/// Get the donations a campaign received on the blockchain
+pub async fn get_donations_from_chain(
+    config: &Config,
+    campaign_id: u32,
+) -> Result<Vec<serde_json::Value>, AppError> {
+    tracing::info!("Fetching donations from chain: campaign_id={}", campaign_id);
+
+    let signer = signer(config)?;
+    let value = SorobanRpcClient::from_config(config)
+        .simulate_contract_call(
+            &signer.account_id(),
+            &config.contract_id,
+            "get_donations",
+            vec![ScVal::from(campaign_id)],
+        )
+        .await?;
+
+    match scval_to_json(&value) {
+        serde_json::Value::Array(donations) => Ok(donations),
+        other => Err(AppError::Stellar(format!(
+            "get_donations returned unexpected value {}",
+            other
+        ))),
+    }
+}
+
+/// Fetch a submitted transaction and the contract call it made
+pub async fn get_transaction_from_chain(
+    config: &Config,
+    tx_hash: &str,
+) -> Result<Option<ChainTransaction>, AppError> {
+    tracing::info!("Fetching transaction from chain: tx_hash={}", tx_hash);
+
+    let tx = SorobanRpcClient::from_config(config)
+        .get_transaction(tx_hash)
+        .await?;
+    if tx.status == "NOT_FOUND" {
+        return Ok(None);
+    }
 
-    Ok(serde_json::json!({
-        "id": campaign_id,
-        "on_chain": true
+    let call = match tx.envelope_xdr {
+        Some(envelope) => invoked_contract_from_envelope(&envelope)?.map(|invoked| ContractCall {
+            contract_id: invoked.contract_id,
+            function: invoked.function,
+            args: invoked.args.iter().map(scval_to_json).collect(),
+        }),
+        None => None,
+    };
+
+    Ok(Some(ChainTransaction {
+        successful: tx.status == "SUCCESS",
+        call,
     }))
 }
 
@@ -104,12 +307,45 @@ pub async fn close_campaign_on_chain(
 ) -> Result<(), AppError> {
     tracing::info!("Closing campaign on-chain: campaign_id={}", campaign_id);
 
-    // Mock implementation - in production, call:
-    // stellar_sdk::invoke_contract(
-    //     &config.contract_id,
-    //     "close_campaign",
-    //     vec![campaign_id]
-    // )
+    invoke(config, "close_campaign", vec![ScVal::from(campaign_id)]).await?;
 
     Ok(())
 }
+
+async fn invoke(
+    config: &Config,
+    function: &str,
+    args: Vec<ScVal>,
+) -> Result<(String, ScVal), AppError> {
+    let signer = signer(config)?;
+    SorobanRpcClient::from_config(config)
+        .invoke_contract(&signer, &config.contract_id, function, args)
+        .await
+}
+
+fn signer(config: &Config) -> Result<StellarSigner, AppError> {
+    if config.contract_id.is_empty() {
+        return Err(AppError::Stellar("CONTRACT_ID is not configured".to_string()));
+    }
+    let secret = config
+        .signer_secret_key
+        .as_deref()
+        .ok_or_else(|| AppError::Stellar("STELLAR_SECRET_KEY is not configured".to_string()))?;
+    StellarSigner::from_secret(secret)
+}
+
+fn address_arg(address: &str) -> Result<ScVal, AppError> {
+    ScAddress::from_str(address)
+        .map(ScVal::Address)
+        .map_err(|_| AppError::BadRequest(format!("Invalid Stellar address {}", address)))
+}
+
+fn string_arg(value: &str) -> Result<ScVal, AppError> {
+    Ok(ScVal::String(ScString(value.try_into().map_err(xdr_error)?)))
+}
+
+/// Hex-encoded SHA-256 digest as a `BytesN<32>` argument
+fn hash32_arg(hex: &str) -> Result<ScVal, AppError> {
+    let bytes = decode_hash32(hex)?;
+    Ok(ScVal::Bytes(ScBytes(bytes.to_vec().try_into().map_err(xdr_error)?)))
+}
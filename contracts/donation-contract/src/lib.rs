@@ -1,5 +1,22 @@
 ﻿#![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, String, Vec};
+use soroban_sdk::{
+    contract, contracterror, contractevent, contractimpl, contracttype, Address, BytesN, Env, String, Vec,
+};
+
+/// Maximum length in bytes of a disbursement purpose
+const MAX_PURPOSE_LEN: u32 = 256;
+/// Maximum number of evidence hashes attached to a single disbursement
+const MAX_EVIDENCE_HASHES: u32 = 10;
+/// Number of auditor votes needed to resolve a dispute unless configured
+const DEFAULT_AUDITOR_QUORUM: u32 = 2;
+/// Maximum number of campaigns returned by `list_campaigns` and `get_org_campaigns`
+const MAX_PAGE_SIZE: u32 = 50;
+/// Maximum number of campaigns `list_campaigns` reads per call, matching or not
+const MAX_SCAN: u32 = 100;
+/// Seconds donors have to consent to a proposed surplus transfer (7 days)
+const CONSENT_WINDOW: u64 = 7 * 24 * 60 * 60;
+/// Joint campaign shares are expressed in basis points of this total
+const TOTAL_SHARE_BPS: u32 = 10_000;
 
 /// Campaign data structure
 #[derive(Clone)]
@@ -12,6 +29,17 @@ pub struct Campaign {
     pub raised: i128,
     pub deadline: u64,
     pub active: bool,
+    /// Parent campaign this peer-to-peer fundraiser rolls up into
+    pub parent: Option<u32>,
+}
+
+/// One page of `list_campaigns`
+#[derive(Clone)]
+#[contracttype]
+pub struct CampaignPage {
+    pub campaigns: Vec<Campaign>,
+    /// `start` for the next call, or 0 once every campaign has been read
+    pub next_start: u32,
 }
 
 /// Donation data structure
@@ -23,6 +51,201 @@ pub struct Donation {
     pub timestamp: u64,
 }
 
+/// Disbursement data structure
+///
+/// `evidence` holds SHA-256 hashes of the supporting documents (invoices,
+/// photos) kept off-chain, so they can later be proven untampered.
+#[derive(Clone)]
+#[contracttype]
+pub struct Disbursement {
+    pub recipient: Address,
+    pub amount: i128,
+    pub purpose: String,
+    pub evidence: Vec<BytesN<32>>,
+    pub timestamp: u64,
+}
+
+/// Dispute opened by an auditor against a campaign
+///
+/// While `resolved` is false, disbursements, voucher activity and
+/// joint-campaign share claims from the campaign are blocked.
+/// `sequence` numbers the campaign's disputes from 1, so earlier ones stay
+/// readable after a new one is opened.
+/// `resolution_votes` collects auditors who voted to resolve it.
+#[derive(Clone)]
+#[contracttype]
+pub struct Dispute {
+    pub campaign_id: u32,
+    pub sequence: u32,
+    pub opened_by: Address,
+    pub reason_hash: BytesN<32>,
+    pub opened_at: u64,
+    pub resolved: bool,
+    pub resolved_at: u64,
+    pub resolution_votes: Vec<Address>,
+}
+
+/// Proposal to move surplus (`raised - goal`) between two campaigns of the same org
+///
+/// Executable once the admin approves it, or once donors of the source campaign
+/// holding more than half of its donated amount consent before `consent_deadline`.
+#[derive(Clone)]
+#[contracttype]
+pub struct SurplusTransfer {
+    pub id: u32,
+    pub from_campaign: u32,
+    pub to_campaign: u32,
+    pub amount: i128,
+    pub proposed_at: u64,
+    pub consent_deadline: u64,
+    pub admin_approved: bool,
+    pub consents: Vec<Address>,
+    pub consent_weight: i128,
+    pub executed: bool,
+}
+
+/// Event published when a surplus transfer is executed
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SurplusTransferred {
+    #[topic]
+    pub from_campaign: u32,
+    #[topic]
+    pub to_campaign: u32,
+    pub transfer_id: u32,
+    pub amount: i128,
+}
+
+/// What `donate` does with donations once a campaign's goal is reached
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum OverflowPolicy {
+    /// Accept donations without limit
+    Unlimited,
+    /// Reject donations once `raised` has reached `goal`
+    RejectAtGoal,
+    /// Accept donations as long as `raised` stays within the given cap
+    HardCap(i128),
+}
+
+/// Optional per-campaign donation limits enforced by `donate`
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct DonationLimits {
+    pub min_donation: Option<i128>,
+    pub max_per_donor: Option<i128>,
+    pub overflow: OverflowPolicy,
+}
+
+/// Errors returned when configuring or enforcing campaign donation limits,
+/// and when paying out a joint campaign
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum DonationError {
+    BelowMinimumDonation = 1,
+    DonorCapExceeded = 2,
+    GoalReached = 3,
+    HardCapExceeded = 4,
+    InvalidLimits = 5,
+    Disputed = 6,
+}
+
+/// Non-transferable proof of a donation, identified by a unique receipt id
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct DonationReceipt {
+    pub id: u32,
+    pub donor: Address,
+    pub campaign_id: u32,
+    pub amount: i128,
+    pub timestamp: u64,
+}
+
+/// Event published when a donation receipt is issued
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReceiptIssued {
+    #[topic]
+    pub donor: Address,
+    #[topic]
+    pub campaign_id: u32,
+    pub receipt_id: u32,
+    pub amount: i128,
+}
+
+/// Event published when a merchant redeems spent vouchers for the underlying token
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VoucherRedeemed {
+    #[topic]
+    pub campaign_id: u32,
+    #[topic]
+    pub merchant: Address,
+    pub amount: i128,
+}
+
+/// Owners of a joint campaign with their agreed split in basis points
+///
+/// `threshold` owners must sign to close the campaign or disburse from it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct CoOwners {
+    pub owners: Vec<Address>,
+    pub shares_bps: Vec<u32>,
+    pub threshold: u32,
+}
+
+/// Event published when a joint campaign owner claims its share
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ShareClaimed {
+    #[topic]
+    pub campaign_id: u32,
+    #[topic]
+    pub owner: Address,
+    pub amount: i128,
+}
+
+/// Event published when a campaign or sub-campaign is created
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CampaignCreated {
+    #[topic]
+    pub campaign_id: u32,
+    #[topic]
+    pub org: Address,
+    pub name: String,
+    pub goal: i128,
+    pub deadline: u64,
+    /// Campaign a fundraiser page rolls up into
+    pub parent: Option<u32>,
+}
+
+/// Event published for every accepted donation
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DonationReceived {
+    #[topic]
+    pub campaign_id: u32,
+    #[topic]
+    pub donor: Address,
+    pub amount: i128,
+}
+
+/// Event published when funds leave a campaign
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FundsDisbursed {
+    #[topic]
+    pub campaign_id: u32,
+    #[topic]
+    pub recipient: Address,
+    pub amount: i128,
+    pub purpose: String,
+    pub evidence: Vec<BytesN<32>>,
+}
+
 /// Storage keys
 #[contracttype]
 pub enum DataKey {
@@ -30,7 +253,220 @@ pub enum DataKey {
     CampaignCount,
     Campaign(u32),
     CampaignDonations(u32),
+    CampaignDisbursements(u32),
     TotalDonationsCount,
+    Auditors,
+    AuditorQuorum,
+    CampaignDispute(u32, u32),
+    LatestDispute(u32),
+    DonorTotal(Address),
+    DonorCampaigns(Address),
+    OrgCampaigns(Address),
+    SurplusTransferCount,
+    SurplusTransfer(u32),
+    CampaignLimits(u32),
+    CampaignDonorTotal(u32, Address),
+    ReceiptCount,
+    Receipt(u32),
+    CampaignMerchants(u32),
+    VoucherBalance(u32, Address),
+    MerchantBalance(u32, Address),
+    ChildCampaigns(u32),
+    CampaignCoOwners(u32),
+    Distributable(u32),
+    ShareClaimed(u32, Address),
+    CampaignTotalRaised(u32),
+}
+
+fn require_admin(env: &Env) -> Address {
+    let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Contract not initialized");
+    admin.require_auth();
+    admin
+}
+
+fn require_auditor(env: &Env, auditor: &Address) {
+    auditor.require_auth();
+    let auditors: Vec<Address> = env.storage().instance().get(&DataKey::Auditors).unwrap_or(Vec::new(env));
+    if !auditors.contains(auditor) {
+        panic!("Caller is not an auditor");
+    }
+}
+
+/// Amount given to a campaign, including through its fundraiser pages
+fn donated_by(env: &Env, campaign_id: u32, donor: Option<&Address>) -> i128 {
+    let children: Vec<u32> = env.storage().persistent().get(&DataKey::ChildCampaigns(campaign_id)).unwrap_or(Vec::new(env));
+    let mut total = 0;
+    for id in core::iter::once(campaign_id).chain(children.iter()) {
+        let donations: Vec<Donation> = env.storage().persistent().get(&DataKey::CampaignDonations(id)).unwrap_or(Vec::new(env));
+        total += donations
+            .iter()
+            .filter(|d| donor.is_none_or(|donor| d.donor == *donor))
+            .map(|d| d.amount)
+            .sum::<i128>();
+    }
+    total
+}
+
+/// Everything ever donated to a campaign. Unlike `Campaign::raised`, this
+/// never goes down when funds are disbursed, allocated or transferred out.
+fn total_raised(env: &Env, campaign_id: u32) -> i128 {
+    env.storage().persistent().get(&DataKey::CampaignTotalRaised(campaign_id)).unwrap_or(0)
+}
+
+/// Check a donation against the campaign's `DonationLimits`, if it has any.
+/// `donor_total` is what the donor has already given to this campaign.
+/// The overflow policy applies to the campaign's total raised, so spending
+/// funds does not reopen a capped campaign.
+fn check_limits(env: &Env, campaign: &Campaign, donor_total: i128, amount: i128) -> Result<(), DonationError> {
+    let Some(limits) = env.storage().persistent().get::<_, DonationLimits>(&DataKey::CampaignLimits(campaign.id)) else {
+        return Ok(());
+    };
+    if limits.min_donation.is_some_and(|min| amount < min) {
+        return Err(DonationError::BelowMinimumDonation);
+    }
+    if limits.max_per_donor.is_some_and(|max| donor_total + amount > max) {
+        return Err(DonationError::DonorCapExceeded);
+    }
+    let total = total_raised(env, campaign.id);
+    match limits.overflow {
+        OverflowPolicy::Unlimited => Ok(()),
+        OverflowPolicy::RejectAtGoal if total >= campaign.goal => Err(DonationError::GoalReached),
+        OverflowPolicy::RejectAtGoal => Ok(()),
+        OverflowPolicy::HardCap(cap) if total + amount > cap => Err(DonationError::HardCapExceeded),
+        OverflowPolicy::HardCap(_) => Ok(()),
+    }
+}
+
+fn store_new_campaign(env: &Env, org: Address, name: String, goal: i128, deadline: u64, parent: Option<u32>) -> u32 {
+    if goal <= 0 {
+        panic!("Goal must be greater than 0");
+    }
+    let current_time = env.ledger().timestamp();
+    if deadline <= current_time {
+        panic!("Deadline must be in the future");
+    }
+    let mut campaign_count: u32 = env.storage().instance().get(&DataKey::CampaignCount).unwrap_or(0);
+    campaign_count += 1;
+    // Fundraiser pages belong to their parent, not to the fundraiser's org
+    if parent.is_none() {
+        let mut org_campaigns: Vec<u32> = env.storage().persistent().get(&DataKey::OrgCampaigns(org.clone())).unwrap_or(Vec::new(env));
+        org_campaigns.push_back(campaign_count);
+        env.storage().persistent().set(&DataKey::OrgCampaigns(org.clone()), &org_campaigns);
+    }
+    CampaignCreated {
+        campaign_id: campaign_count,
+        org: org.clone(),
+        name: name.clone(),
+        goal,
+        deadline,
+        parent,
+    }
+    .publish(env);
+    let campaign = Campaign {
+        id: campaign_count,
+        name,
+        org,
+        goal,
+        raised: 0,
+        deadline,
+        active: true,
+        parent,
+    };
+    env.storage().persistent().set(&DataKey::Campaign(campaign_count), &campaign);
+    env.storage().instance().set(&DataKey::CampaignCount, &campaign_count);
+    let donations: Vec<Donation> = Vec::new(env);
+    env.storage().persistent().set(&DataKey::CampaignDonations(campaign_count), &donations);
+    campaign_count
+}
+
+/// Sub-campaign balances only track fundraising progress; the pooled funds
+/// live in the parent campaign.
+fn require_not_child(campaign: &Campaign) {
+    if campaign.parent.is_some() {
+        panic!("Sub-campaign funds are held by the parent campaign");
+    }
+}
+
+fn record_disbursement(
+    env: &Env,
+    mut campaign: Campaign,
+    recipient: Address,
+    amount: i128,
+    purpose: String,
+    evidence: Vec<BytesN<32>>,
+) {
+    require_not_child(&campaign);
+    if amount <= 0 {
+        panic!("Disbursement amount must be greater than 0");
+    }
+    if amount > campaign.raised {
+        panic!("Insufficient funds in campaign");
+    }
+    if is_disputed(env, campaign.id) {
+        panic!("Campaign is under dispute");
+    }
+    if purpose.is_empty() || purpose.len() > MAX_PURPOSE_LEN {
+        panic!("Purpose must be between 1 and 256 bytes");
+    }
+    if evidence.is_empty() {
+        panic!("At least one evidence hash is required");
+    }
+    if evidence.len() > MAX_EVIDENCE_HASHES {
+        panic!("Too many evidence hashes");
+    }
+    campaign.raised -= amount;
+    env.storage().persistent().set(&DataKey::Campaign(campaign.id), &campaign);
+    FundsDisbursed {
+        campaign_id: campaign.id,
+        recipient: recipient.clone(),
+        amount,
+        purpose: purpose.clone(),
+        evidence: evidence.clone(),
+    }
+    .publish(env);
+    let disbursement = Disbursement {
+        recipient,
+        amount,
+        purpose,
+        evidence,
+        timestamp: env.ledger().timestamp(),
+    };
+    let mut campaign_disbursements: Vec<Disbursement> = env.storage().persistent().get(&DataKey::CampaignDisbursements(campaign.id)).unwrap_or(Vec::new(env));
+    campaign_disbursements.push_back(disbursement);
+    env.storage().persistent().set(&DataKey::CampaignDisbursements(campaign.id), &campaign_disbursements);
+}
+
+/// Joint campaigns can only move funds or close with the owner threshold.
+fn require_single_owner(env: &Env, campaign_id: u32) {
+    if env.storage().persistent().has(&DataKey::CampaignCoOwners(campaign_id)) {
+        panic!("Joint campaign requires owner signatures");
+    }
+}
+
+fn require_owner_threshold(env: &Env, campaign_id: u32, signers: &Vec<Address>) {
+    let co_owners: CoOwners = env.storage().persistent().get(&DataKey::CampaignCoOwners(campaign_id)).expect("Campaign is not jointly owned");
+    for (i, signer) in signers.iter().enumerate() {
+        if !co_owners.owners.contains(&signer) {
+            panic!("Signer is not a campaign owner");
+        }
+        if signers.first_index_of(&signer) != Some(i as u32) {
+            panic!("Duplicate signer");
+        }
+        signer.require_auth();
+    }
+    if signers.len() < co_owners.threshold {
+        panic!("Not enough owner signatures");
+    }
+}
+
+/// The campaign's most recent dispute, which is the only one that can be open
+fn latest_dispute(env: &Env, campaign_id: u32) -> Option<Dispute> {
+    let sequence: u32 = env.storage().persistent().get(&DataKey::LatestDispute(campaign_id))?;
+    env.storage().persistent().get(&DataKey::CampaignDispute(campaign_id, sequence))
+}
+
+fn is_disputed(env: &Env, campaign_id: u32) -> bool {
+    latest_dispute(env, campaign_id).is_some_and(|d| !d.resolved)
 }
 
 #[contract]
@@ -50,32 +486,42 @@ impl DonationContract {
 
     pub fn create_campaign(env: Env, org: Address, name: String, goal: i128, deadline: u64) -> u32 {
         org.require_auth();
-        if goal <= 0 {
-            panic!("Goal must be greater than 0");
+        store_new_campaign(&env, org, name, goal, deadline, None)
+    }
+
+    /// Creates a fundraiser page under `parent_id`. Donations to it count toward
+    /// its own goal and the parent's `raised`; funds are disbursed by the parent's org.
+    pub fn create_child_campaign(
+        env: Env,
+        parent_id: u32,
+        fundraiser: Address,
+        name: String,
+        goal: i128,
+        deadline: u64,
+    ) -> u32 {
+        fundraiser.require_auth();
+        let parent: Campaign = env.storage().persistent().get(&DataKey::Campaign(parent_id)).expect("Campaign not found");
+        if parent.parent.is_some() {
+            panic!("Sub-campaigns cannot be nested");
         }
-        let current_time = env.ledger().timestamp();
-        if deadline <= current_time {
-            panic!("Deadline must be in the future");
-        }
-        let mut campaign_count: u32 = env.storage().instance().get(&DataKey::CampaignCount).unwrap_or(0);
-        campaign_count += 1;
-        let campaign = Campaign {
-            id: campaign_count,
-            name,
-            org,
-            goal,
-            raised: 0,
-            deadline,
-            active: true,
-        };
-        env.storage().persistent().set(&DataKey::Campaign(campaign_count), &campaign);
-        env.storage().instance().set(&DataKey::CampaignCount, &campaign_count);
-        let donations: Vec<Donation> = Vec::new(&env);
-        env.storage().persistent().set(&DataKey::CampaignDonations(campaign_count), &donations);
-        campaign_count
+        if !parent.active {
+            panic!("Campaign is not active");
+        }
+        if deadline > parent.deadline {
+            panic!("Deadline cannot be after the parent campaign's deadline");
+        }
+        let campaign_id = store_new_campaign(&env, fundraiser, name, goal, deadline, Some(parent_id));
+        let mut children: Vec<u32> = env.storage().persistent().get(&DataKey::ChildCampaigns(parent_id)).unwrap_or(Vec::new(&env));
+        children.push_back(campaign_id);
+        env.storage().persistent().set(&DataKey::ChildCampaigns(parent_id), &children);
+        campaign_id
     }
 
-    pub fn donate(env: Env, campaign_id: u32, donor: Address, amount: i128) {
+    pub fn get_child_campaigns(env: Env, parent_id: u32) -> Vec<u32> {
+        env.storage().persistent().get(&DataKey::ChildCampaigns(parent_id)).unwrap_or(Vec::new(&env))
+    }
+
+    pub fn donate(env: Env, campaign_id: u32, donor: Address, amount: i128) -> Result<(), DonationError> {
         donor.require_auth();
         if amount <= 0 {
             panic!("Donation amount must be greater than 0");
@@ -88,41 +534,479 @@ impl DonationContract {
         if current_time > campaign.deadline {
             panic!("Campaign deadline has passed");
         }
+        let mut parent: Option<Campaign> = campaign.parent.map(|parent_id| {
+            env.storage().persistent().get(&DataKey::Campaign(parent_id)).expect("Campaign not found")
+        });
+        if parent.as_ref().is_some_and(|parent| !parent.active) {
+            panic!("Parent campaign is not active");
+        }
+        let campaign_donor_total: i128 = env.storage().persistent().get(&DataKey::CampaignDonorTotal(campaign_id, donor.clone())).unwrap_or(0);
+        check_limits(&env, &campaign, campaign_donor_total, amount)?;
+        // A fundraiser page donation also lands in the parent, so the parent's limits apply too
+        let parent_donor_total: i128 = match parent.as_ref() {
+            Some(parent) => {
+                let total = env.storage().persistent().get(&DataKey::CampaignDonorTotal(parent.id, donor.clone())).unwrap_or(0);
+                check_limits(&env, parent, total, amount)?;
+                total
+            }
+            None => 0,
+        };
         campaign.raised += amount;
         env.storage().persistent().set(&DataKey::Campaign(campaign_id), &campaign);
+        env.storage().persistent().set(&DataKey::CampaignTotalRaised(campaign_id), &(total_raised(&env, campaign_id) + amount));
+        if let Some(parent) = parent.as_mut() {
+            parent.raised += amount;
+            env.storage().persistent().set(&DataKey::Campaign(parent.id), parent);
+            env.storage().persistent().set(&DataKey::CampaignTotalRaised(parent.id), &(total_raised(&env, parent.id) + amount));
+            env.storage().persistent().set(&DataKey::CampaignDonorTotal(parent.id, donor.clone()), &(parent_donor_total + amount));
+        }
+        env.storage().persistent().set(&DataKey::CampaignDonorTotal(campaign_id, donor.clone()), &(campaign_donor_total + amount));
         let donation = Donation { donor: donor.clone(), amount, timestamp: current_time };
         let mut campaign_donations: Vec<Donation> = env.storage().persistent().get(&DataKey::CampaignDonations(campaign_id)).unwrap_or(Vec::new(&env));
         campaign_donations.push_back(donation);
         env.storage().persistent().set(&DataKey::CampaignDonations(campaign_id), &campaign_donations);
+        let donor_total: i128 = env.storage().persistent().get(&DataKey::DonorTotal(donor.clone())).unwrap_or(0);
+        env.storage().persistent().set(&DataKey::DonorTotal(donor.clone()), &(donor_total + amount));
+        let mut donor_campaigns: Vec<u32> = env.storage().persistent().get(&DataKey::DonorCampaigns(donor.clone())).unwrap_or(Vec::new(&env));
+        if !donor_campaigns.contains(campaign_id) {
+            donor_campaigns.push_back(campaign_id);
+            env.storage().persistent().set(&DataKey::DonorCampaigns(donor.clone()), &donor_campaigns);
+        }
         let mut total_donations: u32 = env.storage().instance().get(&DataKey::TotalDonationsCount).unwrap_or(0);
         total_donations += 1;
         env.storage().instance().set(&DataKey::TotalDonationsCount, &total_donations);
+        DonationReceived { campaign_id, donor, amount }.publish(&env);
+        Ok(())
+    }
+
+    /// Donates like `donate` and issues a receipt to the donor, returning its id.
+    pub fn donate_with_receipt(env: Env, campaign_id: u32, donor: Address, amount: i128) -> Result<u32, DonationError> {
+        Self::donate(env.clone(), campaign_id, donor.clone(), amount)?;
+        let mut receipt_count: u32 = env.storage().instance().get(&DataKey::ReceiptCount).unwrap_or(0);
+        receipt_count += 1;
+        let receipt = DonationReceipt {
+            id: receipt_count,
+            donor: donor.clone(),
+            campaign_id,
+            amount,
+            timestamp: env.ledger().timestamp(),
+        };
+        env.storage().persistent().set(&DataKey::Receipt(receipt_count), &receipt);
+        env.storage().instance().set(&DataKey::ReceiptCount, &receipt_count);
+        ReceiptIssued {
+            donor,
+            campaign_id,
+            receipt_id: receipt_count,
+            amount,
+        }
+        .publish(&env);
+        Ok(receipt_count)
+    }
+
+    /// Returns the receipt with the given id, or `None` if it was never issued.
+    pub fn verify_receipt(env: Env, id: u32) -> Option<DonationReceipt> {
+        env.storage().persistent().get(&DataKey::Receipt(id))
+    }
+
+    pub fn set_donation_limits(env: Env, campaign_id: u32, limits: DonationLimits) -> Result<(), DonationError> {
+        let campaign: Campaign = env.storage().persistent().get(&DataKey::Campaign(campaign_id)).expect("Campaign not found");
+        campaign.org.require_auth();
+        let min = limits.min_donation.unwrap_or(0);
+        let valid = min >= 0
+            && limits.max_per_donor.is_none_or(|max| max > 0 && max >= min)
+            && match limits.overflow {
+                OverflowPolicy::HardCap(cap) => cap >= campaign.goal,
+                _ => true,
+            };
+        if !valid {
+            return Err(DonationError::InvalidLimits);
+        }
+        env.storage().persistent().set(&DataKey::CampaignLimits(campaign_id), &limits);
+        Ok(())
+    }
+
+    /// Total ever donated to a campaign, including its fundraiser pages
+    pub fn get_total_raised(env: Env, campaign_id: u32) -> i128 {
+        total_raised(&env, campaign_id)
+    }
+
+    pub fn get_donation_limits(env: Env, campaign_id: u32) -> DonationLimits {
+        env.storage().persistent().get(&DataKey::CampaignLimits(campaign_id)).unwrap_or(DonationLimits {
+            min_donation: None,
+            max_per_donor: None,
+            overflow: OverflowPolicy::Unlimited,
+        })
+    }
+
+    pub fn disburse(
+        env: Env,
+        campaign_id: u32,
+        recipient: Address,
+        amount: i128,
+        purpose: String,
+        evidence: Vec<BytesN<32>>,
+    ) {
+        let campaign: Campaign = env.storage().persistent().get(&DataKey::Campaign(campaign_id)).expect("Campaign not found");
+        campaign.org.require_auth();
+        require_single_owner(&env, campaign_id);
+        record_disbursement(&env, campaign, recipient, amount, purpose, evidence);
+    }
+
+    /// Creates a campaign run jointly by several organizations. Every owner must
+    /// sign creation to agree to the split; afterwards `threshold` owners must
+    /// sign closing and disbursements.
+    pub fn create_joint_campaign(env: Env, co_owners: CoOwners, name: String, goal: i128, deadline: u64) -> u32 {
+        if co_owners.owners.len() < 2 || co_owners.owners.len() != co_owners.shares_bps.len() {
+            panic!("Joint campaigns need at least two owners with one share each");
+        }
+        if co_owners.threshold == 0 || co_owners.threshold > co_owners.owners.len() {
+            panic!("Threshold must be between 1 and the number of owners");
+        }
+        let total_bps: u32 = co_owners.shares_bps.iter().sum();
+        if total_bps != TOTAL_SHARE_BPS {
+            panic!("Shares must add up to 10000 basis points");
+        }
+        for (i, owner) in co_owners.owners.iter().enumerate() {
+            if co_owners.owners.first_index_of(&owner) != Some(i as u32) {
+                panic!("Duplicate owner");
+            }
+            owner.require_auth();
+        }
+        let lead = co_owners.owners.get(0).unwrap();
+        let campaign_id = store_new_campaign(&env, lead, name, goal, deadline, None);
+        for owner in co_owners.owners.iter().skip(1) {
+            let mut org_campaigns: Vec<u32> = env.storage().persistent().get(&DataKey::OrgCampaigns(owner.clone())).unwrap_or(Vec::new(&env));
+            org_campaigns.push_back(campaign_id);
+            env.storage().persistent().set(&DataKey::OrgCampaigns(owner), &org_campaigns);
+        }
+        env.storage().persistent().set(&DataKey::CampaignCoOwners(campaign_id), &co_owners);
+        campaign_id
+    }
+
+    pub fn disburse_joint(
+        env: Env,
+        campaign_id: u32,
+        signers: Vec<Address>,
+        recipient: Address,
+        amount: i128,
+        purpose: String,
+        evidence: Vec<BytesN<32>>,
+    ) {
+        let campaign: Campaign = env.storage().persistent().get(&DataKey::Campaign(campaign_id)).expect("Campaign not found");
+        require_owner_threshold(&env, campaign_id, &signers);
+        // Funds left in a closed joint campaign are settled by `claim_share` instead
+        if !campaign.active {
+            panic!("Campaign is not active");
+        }
+        record_disbursement(&env, campaign, recipient, amount, purpose, evidence);
+    }
+
+    pub fn close_joint_campaign(env: Env, id: u32, signers: Vec<Address>) {
+        let mut campaign: Campaign = env.storage().persistent().get(&DataKey::Campaign(id)).expect("Campaign not found");
+        require_owner_threshold(&env, id, &signers);
+        if !campaign.active {
+            panic!("Campaign already closed");
+        }
+        campaign.active = false;
+        env.storage().persistent().set(&DataKey::Campaign(id), &campaign);
+        env.storage().persistent().set(&DataKey::Distributable(id), &campaign.raised);
+    }
+
+    /// Pays an owner its agreed share of the funds left when the joint campaign
+    /// was closed. The payout is settled off the `ShareClaimed` event.
+    pub fn claim_share(env: Env, campaign_id: u32, owner: Address) -> Result<i128, DonationError> {
+        owner.require_auth();
+        let co_owners: CoOwners = env.storage().persistent().get(&DataKey::CampaignCoOwners(campaign_id)).expect("Campaign is not jointly owned");
+        let distributable: i128 = env.storage().persistent().get(&DataKey::Distributable(campaign_id)).expect("Campaign is still active");
+        let index = co_owners.owners.first_index_of(&owner).expect("Caller is not a campaign owner");
+        if env.storage().persistent().has(&DataKey::ShareClaimed(campaign_id, owner.clone())) {
+            panic!("Share already claimed");
+        }
+        if is_disputed(&env, campaign_id) {
+            return Err(DonationError::Disputed);
+        }
+        let share = distributable * co_owners.shares_bps.get(index).unwrap() as i128 / TOTAL_SHARE_BPS as i128;
+        let mut campaign: Campaign = env.storage().persistent().get(&DataKey::Campaign(campaign_id)).expect("Campaign not found");
+        if share > campaign.raised {
+            panic!("Insufficient funds in campaign");
+        }
+        campaign.raised -= share;
+        env.storage().persistent().set(&DataKey::Campaign(campaign_id), &campaign);
+        env.storage().persistent().set(&DataKey::ShareClaimed(campaign_id, owner.clone()), &share);
+        ShareClaimed { campaign_id, owner, amount: share }.publish(&env);
+        Ok(share)
+    }
+
+    pub fn get_co_owners(env: Env, campaign_id: u32) -> Option<CoOwners> {
+        env.storage().persistent().get(&DataKey::CampaignCoOwners(campaign_id))
+    }
+
+    pub fn register_merchant(env: Env, campaign_id: u32, merchant: Address) {
+        let campaign: Campaign = env.storage().persistent().get(&DataKey::Campaign(campaign_id)).expect("Campaign not found");
+        campaign.org.require_auth();
+        let mut merchants: Vec<Address> = env.storage().persistent().get(&DataKey::CampaignMerchants(campaign_id)).unwrap_or(Vec::new(&env));
+        if merchants.contains(&merchant) {
+            panic!("Merchant already registered");
+        }
+        merchants.push_back(merchant);
+        env.storage().persistent().set(&DataKey::CampaignMerchants(campaign_id), &merchants);
     }
 
-    pub fn disburse(env: Env, campaign_id: u32, recipient: Address, amount: i128) {
+    /// Moves `amount` from the campaign's raised balance into a voucher balance
+    /// the beneficiary can only spend at the campaign's registered merchants.
+    pub fn allocate_voucher(env: Env, campaign_id: u32, beneficiary: Address, amount: i128) {
         let mut campaign: Campaign = env.storage().persistent().get(&DataKey::Campaign(campaign_id)).expect("Campaign not found");
         campaign.org.require_auth();
+        require_not_child(&campaign);
+        require_single_owner(&env, campaign_id);
         if amount <= 0 {
-            panic!("Disbursement amount must be greater than 0");
+            panic!("Voucher amount must be greater than 0");
         }
         if amount > campaign.raised {
             panic!("Insufficient funds in campaign");
         }
+        if !campaign.active {
+            panic!("Campaign is not active");
+        }
+        if is_disputed(&env, campaign_id) {
+            panic!("Campaign is under dispute");
+        }
         campaign.raised -= amount;
         env.storage().persistent().set(&DataKey::Campaign(campaign_id), &campaign);
+        let key = DataKey::VoucherBalance(campaign_id, beneficiary);
+        let balance: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        env.storage().persistent().set(&key, &(balance + amount));
+    }
+
+    pub fn spend_voucher(env: Env, campaign_id: u32, beneficiary: Address, merchant: Address, amount: i128) {
+        beneficiary.require_auth();
+        if amount <= 0 {
+            panic!("Voucher amount must be greater than 0");
+        }
+        if is_disputed(&env, campaign_id) {
+            panic!("Campaign is under dispute");
+        }
+        let merchants: Vec<Address> = env.storage().persistent().get(&DataKey::CampaignMerchants(campaign_id)).unwrap_or(Vec::new(&env));
+        if !merchants.contains(&merchant) {
+            panic!("Merchant not registered for campaign");
+        }
+        let voucher_key = DataKey::VoucherBalance(campaign_id, beneficiary);
+        let voucher_balance: i128 = env.storage().persistent().get(&voucher_key).unwrap_or(0);
+        if amount > voucher_balance {
+            panic!("Insufficient voucher balance");
+        }
+        env.storage().persistent().set(&voucher_key, &(voucher_balance - amount));
+        let merchant_key = DataKey::MerchantBalance(campaign_id, merchant);
+        let merchant_balance: i128 = env.storage().persistent().get(&merchant_key).unwrap_or(0);
+        env.storage().persistent().set(&merchant_key, &(merchant_balance + amount));
+    }
+
+    /// Redeems a merchant's spent vouchers; the payout is settled off the
+    /// `VoucherRedeemed` event, like `disburse`.
+    pub fn redeem_voucher(env: Env, campaign_id: u32, merchant: Address, amount: i128) {
+        merchant.require_auth();
+        if amount <= 0 {
+            panic!("Redemption amount must be greater than 0");
+        }
+        if is_disputed(&env, campaign_id) {
+            panic!("Campaign is under dispute");
+        }
+        let key = DataKey::MerchantBalance(campaign_id, merchant.clone());
+        let balance: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        if amount > balance {
+            panic!("Insufficient merchant balance");
+        }
+        env.storage().persistent().set(&key, &(balance - amount));
+        VoucherRedeemed { campaign_id, merchant, amount }.publish(&env);
+    }
+
+    pub fn get_campaign_merchants(env: Env, campaign_id: u32) -> Vec<Address> {
+        env.storage().persistent().get(&DataKey::CampaignMerchants(campaign_id)).unwrap_or(Vec::new(&env))
+    }
+
+    pub fn get_voucher_balance(env: Env, campaign_id: u32, beneficiary: Address) -> i128 {
+        env.storage().persistent().get(&DataKey::VoucherBalance(campaign_id, beneficiary)).unwrap_or(0)
+    }
+
+    pub fn get_merchant_balance(env: Env, campaign_id: u32, merchant: Address) -> i128 {
+        env.storage().persistent().get(&DataKey::MerchantBalance(campaign_id, merchant)).unwrap_or(0)
     }
 
     pub fn get_campaign(env: Env, id: u32) -> Campaign {
         env.storage().persistent().get(&DataKey::Campaign(id)).expect("Campaign not found")
     }
 
+    /// Lists up to `limit` campaigns with id >= `start`, optionally keeping
+    /// only campaigns whose `active` flag matches.
+    ///
+    /// At most `MAX_SCAN` campaigns are read per call, so a filtered page can
+    /// come back short or empty; keep calling with `next_start` until it is 0.
+    pub fn list_campaigns(env: Env, start: u32, limit: u32, active: Option<bool>) -> CampaignPage {
+        let limit = limit.min(MAX_PAGE_SIZE);
+        let campaign_count: u32 = env.storage().instance().get(&DataKey::CampaignCount).unwrap_or(0);
+        let mut campaigns: Vec<Campaign> = Vec::new(&env);
+        let mut id = start.max(1);
+        let mut scanned = 0;
+        while id <= campaign_count && campaigns.len() < limit && scanned < MAX_SCAN {
+            let campaign: Campaign = env.storage().persistent().get(&DataKey::Campaign(id)).expect("Campaign not found");
+            if active.is_none_or(|active| campaign.active == active) {
+                campaigns.push_back(campaign);
+            }
+            id += 1;
+            scanned += 1;
+        }
+        let next_start = if id > campaign_count { 0 } else { id };
+        CampaignPage { campaigns, next_start }
+    }
+
+    /// Lists up to `limit` of the organization's campaigns, starting at
+    /// position `start` in the order they were created.
+    pub fn get_org_campaigns(env: Env, org: Address, start: u32, limit: u32) -> Vec<Campaign> {
+        let limit = limit.min(MAX_PAGE_SIZE);
+        let campaign_ids: Vec<u32> = env.storage().persistent().get(&DataKey::OrgCampaigns(org)).unwrap_or(Vec::new(&env));
+        let end = start.saturating_add(limit).min(campaign_ids.len());
+        let mut campaigns: Vec<Campaign> = Vec::new(&env);
+        for index in start..end {
+            let id = campaign_ids.get(index).expect("Campaign not found");
+            campaigns.push_back(env.storage().persistent().get(&DataKey::Campaign(id)).expect("Campaign not found"));
+        }
+        campaigns
+    }
+
     pub fn get_donations(env: Env, campaign_id: u32) -> Vec<Donation> {
         env.storage().persistent().get(&DataKey::CampaignDonations(campaign_id)).unwrap_or(Vec::new(&env))
     }
 
+    pub fn propose_surplus_transfer(env: Env, from_campaign: u32, to_campaign: u32, amount: i128) -> u32 {
+        let source: Campaign = env.storage().persistent().get(&DataKey::Campaign(from_campaign)).expect("Campaign not found");
+        let target: Campaign = env.storage().persistent().get(&DataKey::Campaign(to_campaign)).expect("Campaign not found");
+        source.org.require_auth();
+        require_not_child(&source);
+        require_not_child(&target);
+        require_single_owner(&env, from_campaign);
+        if from_campaign == to_campaign {
+            panic!("Cannot transfer surplus to the same campaign");
+        }
+        if target.org != source.org {
+            panic!("Campaigns belong to different organizations");
+        }
+        if !source.active || !target.active {
+            panic!("Campaign is not active");
+        }
+        if amount <= 0 {
+            panic!("Transfer amount must be greater than 0");
+        }
+        if amount > source.raised - source.goal {
+            panic!("Amount exceeds campaign surplus");
+        }
+        let mut transfer_count: u32 = env.storage().instance().get(&DataKey::SurplusTransferCount).unwrap_or(0);
+        transfer_count += 1;
+        let current_time = env.ledger().timestamp();
+        let transfer = SurplusTransfer {
+            id: transfer_count,
+            from_campaign,
+            to_campaign,
+            amount,
+            proposed_at: current_time,
+            consent_deadline: current_time + CONSENT_WINDOW,
+            admin_approved: false,
+            consents: Vec::new(&env),
+            consent_weight: 0,
+            executed: false,
+        };
+        env.storage().persistent().set(&DataKey::SurplusTransfer(transfer_count), &transfer);
+        env.storage().instance().set(&DataKey::SurplusTransferCount, &transfer_count);
+        transfer_count
+    }
+
+    pub fn approve_surplus_transfer(env: Env, transfer_id: u32) {
+        require_admin(&env);
+        let mut transfer: SurplusTransfer = env.storage().persistent().get(&DataKey::SurplusTransfer(transfer_id)).expect("Surplus transfer not found");
+        if transfer.executed {
+            panic!("Surplus transfer already executed");
+        }
+        transfer.admin_approved = true;
+        env.storage().persistent().set(&DataKey::SurplusTransfer(transfer_id), &transfer);
+    }
+
+    /// Records a donor's consent, weighted by what they gave to the source campaign.
+    pub fn consent_surplus_transfer(env: Env, transfer_id: u32, donor: Address) {
+        donor.require_auth();
+        let mut transfer: SurplusTransfer = env.storage().persistent().get(&DataKey::SurplusTransfer(transfer_id)).expect("Surplus transfer not found");
+        if transfer.executed {
+            panic!("Surplus transfer already executed");
+        }
+        if env.ledger().timestamp() > transfer.consent_deadline {
+            panic!("Consent window has closed");
+        }
+        if transfer.consents.contains(&donor) {
+            panic!("Donor already consented");
+        }
+        let weight = donated_by(&env, transfer.from_campaign, Some(&donor));
+        if weight == 0 {
+            panic!("Donor did not contribute to campaign");
+        }
+        transfer.consents.push_back(donor);
+        transfer.consent_weight += weight;
+        env.storage().persistent().set(&DataKey::SurplusTransfer(transfer_id), &transfer);
+    }
+
+    pub fn execute_surplus_transfer(env: Env, transfer_id: u32) {
+        let mut transfer: SurplusTransfer = env.storage().persistent().get(&DataKey::SurplusTransfer(transfer_id)).expect("Surplus transfer not found");
+        let mut source: Campaign = env.storage().persistent().get(&DataKey::Campaign(transfer.from_campaign)).expect("Campaign not found");
+        let mut target: Campaign = env.storage().persistent().get(&DataKey::Campaign(transfer.to_campaign)).expect("Campaign not found");
+        source.org.require_auth();
+        if transfer.executed {
+            panic!("Surplus transfer already executed");
+        }
+        let donor_consent = transfer.consent_weight * 2 > donated_by(&env, transfer.from_campaign, None);
+        if !transfer.admin_approved && !donor_consent {
+            panic!("Surplus transfer not approved");
+        }
+        if !source.active || !target.active {
+            panic!("Campaign is not active");
+        }
+        if is_disputed(&env, transfer.from_campaign) || is_disputed(&env, transfer.to_campaign) {
+            panic!("Campaign is under dispute");
+        }
+        if transfer.amount > source.raised - source.goal {
+            panic!("Amount exceeds campaign surplus");
+        }
+        source.raised -= transfer.amount;
+        target.raised += transfer.amount;
+        env.storage().persistent().set(&DataKey::Campaign(source.id), &source);
+        env.storage().persistent().set(&DataKey::Campaign(target.id), &target);
+        transfer.executed = true;
+        env.storage().persistent().set(&DataKey::SurplusTransfer(transfer_id), &transfer);
+        SurplusTransferred {
+            from_campaign: source.id,
+            to_campaign: target.id,
+            transfer_id,
+            amount: transfer.amount,
+        }
+        .publish(&env);
+    }
+
+    pub fn get_surplus_transfer(env: Env, transfer_id: u32) -> SurplusTransfer {
+        env.storage().persistent().get(&DataKey::SurplusTransfer(transfer_id)).expect("Surplus transfer not found")
+    }
+
+    pub fn get_donor_total(env: Env, donor: Address) -> i128 {
+        env.storage().persistent().get(&DataKey::DonorTotal(donor)).unwrap_or(0)
+    }
+
+    pub fn get_donor_campaigns(env: Env, donor: Address) -> Vec<u32> {
+        env.storage().persistent().get(&DataKey::DonorCampaigns(donor)).unwrap_or(Vec::new(&env))
+    }
+
+    pub fn get_campaign_disbursements(env: Env, campaign_id: u32) -> Vec<Disbursement> {
+        env.storage().persistent().get(&DataKey::CampaignDisbursements(campaign_id)).unwrap_or(Vec::new(&env))
+    }
+
     pub fn close_campaign(env: Env, id: u32) {
         let mut campaign: Campaign = env.storage().persistent().get(&DataKey::Campaign(id)).expect("Campaign not found");
         campaign.org.require_auth();
+        require_single_owner(&env, id);
         if !campaign.active {
             panic!("Campaign already closed");
         }
@@ -142,6 +1026,119 @@ impl DonationContract {
         env.storage().instance().get(&DataKey::Admin).expect("Contract not initialized")
     }
 
+    pub fn add_auditor(env: Env, auditor: Address) {
+        require_admin(&env);
+        let mut auditors: Vec<Address> = env.storage().instance().get(&DataKey::Auditors).unwrap_or(Vec::new(&env));
+        if auditors.contains(&auditor) {
+            panic!("Auditor already registered");
+        }
+        auditors.push_back(auditor);
+        env.storage().instance().set(&DataKey::Auditors, &auditors);
+    }
+
+    pub fn remove_auditor(env: Env, auditor: Address) {
+        require_admin(&env);
+        let mut auditors: Vec<Address> = env.storage().instance().get(&DataKey::Auditors).unwrap_or(Vec::new(&env));
+        let index = auditors.first_index_of(&auditor).expect("Auditor not found");
+        auditors.remove(index);
+        env.storage().instance().set(&DataKey::Auditors, &auditors);
+    }
+
+    pub fn set_auditor_quorum(env: Env, quorum: u32) {
+        require_admin(&env);
+        if quorum == 0 {
+            panic!("Quorum must be greater than 0");
+        }
+        let auditors: Vec<Address> = env.storage().instance().get(&DataKey::Auditors).unwrap_or(Vec::new(&env));
+        if quorum > auditors.len() {
+            panic!("Quorum exceeds the number of auditors");
+        }
+        env.storage().instance().set(&DataKey::AuditorQuorum, &quorum);
+    }
+
+    pub fn get_auditors(env: Env) -> Vec<Address> {
+        env.storage().instance().get(&DataKey::Auditors).unwrap_or(Vec::new(&env))
+    }
+
+    pub fn get_auditor_quorum(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::AuditorQuorum).unwrap_or(DEFAULT_AUDITOR_QUORUM)
+    }
+
+    /// Opens a dispute against a campaign, blocking disbursements until it is resolved.
+    pub fn open_dispute(env: Env, campaign_id: u32, auditor: Address, reason_hash: BytesN<32>) {
+        require_auditor(&env, &auditor);
+        if !env.storage().persistent().has(&DataKey::Campaign(campaign_id)) {
+            panic!("Campaign not found");
+        }
+        if is_disputed(&env, campaign_id) {
+            panic!("Campaign already under dispute");
+        }
+        let sequence: u32 = env.storage().persistent().get(&DataKey::LatestDispute(campaign_id)).unwrap_or(0) + 1;
+        let dispute = Dispute {
+            campaign_id,
+            sequence,
+            opened_by: auditor,
+            reason_hash,
+            opened_at: env.ledger().timestamp(),
+            resolved: false,
+            resolved_at: 0,
+            resolution_votes: Vec::new(&env),
+        };
+        env.storage().persistent().set(&DataKey::CampaignDispute(campaign_id, sequence), &dispute);
+        env.storage().persistent().set(&DataKey::LatestDispute(campaign_id), &sequence);
+    }
+
+    /// Resolves the campaign's open dispute outright. Only callable by the admin.
+    pub fn resolve_dispute(env: Env, campaign_id: u32) {
+        require_admin(&env);
+        let mut dispute = latest_dispute(&env, campaign_id).expect("Dispute not found");
+        if dispute.resolved {
+            panic!("Dispute already resolved");
+        }
+        dispute.resolved = true;
+        dispute.resolved_at = env.ledger().timestamp();
+        env.storage().persistent().set(&DataKey::CampaignDispute(campaign_id, dispute.sequence), &dispute);
+    }
+
+    /// Records an auditor's vote to resolve the campaign's open dispute; the
+    /// dispute is resolved once the number of votes reaches the auditor quorum.
+    pub fn vote_resolve_dispute(env: Env, campaign_id: u32, auditor: Address) {
+        require_auditor(&env, &auditor);
+        let mut dispute = latest_dispute(&env, campaign_id).expect("Dispute not found");
+        if dispute.resolved {
+            panic!("Dispute already resolved");
+        }
+        if dispute.resolution_votes.contains(&auditor) {
+            panic!("Auditor already voted");
+        }
+        dispute.resolution_votes.push_back(auditor);
+        let quorum: u32 = env.storage().instance().get(&DataKey::AuditorQuorum).unwrap_or(DEFAULT_AUDITOR_QUORUM);
+        if dispute.resolution_votes.len() >= quorum {
+            dispute.resolved = true;
+            dispute.resolved_at = env.ledger().timestamp();
+        }
+        env.storage().persistent().set(&DataKey::CampaignDispute(campaign_id, dispute.sequence), &dispute);
+    }
+
+    /// Returns the campaign's most recent dispute.
+    pub fn get_dispute(env: Env, campaign_id: u32) -> Option<Dispute> {
+        latest_dispute(&env, campaign_id)
+    }
+
+    /// Returns one of the campaign's disputes by sequence number, starting at 1.
+    pub fn get_dispute_at(env: Env, campaign_id: u32, sequence: u32) -> Option<Dispute> {
+        env.storage().persistent().get(&DataKey::CampaignDispute(campaign_id, sequence))
+    }
+
+    /// Number of disputes ever opened against the campaign
+    pub fn get_dispute_count(env: Env, campaign_id: u32) -> u32 {
+        env.storage().persistent().get(&DataKey::LatestDispute(campaign_id)).unwrap_or(0)
+    }
+
+    pub fn is_campaign_disputed(env: Env, campaign_id: u32) -> bool {
+        is_disputed(&env, campaign_id)
+    }
+
     pub fn is_goal_reached(env: Env, campaign_id: u32) -> bool {
         let campaign: Campaign = env.storage().persistent().get(&DataKey::Campaign(campaign_id)).expect("Campaign not found");
         campaign.raised >= campaign.goal
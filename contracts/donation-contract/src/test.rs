@@ -1,158 +1,1063 @@
 #![cfg(test)]
 
 use super::*;
-use soroban_sdk::{testutils::Address as _, Address, Env, String};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, BytesN, Env, String,
+};
+
+const DEADLINE: u64 = 1_000_000;
+
+fn setup(env: &Env) -> (DonationContractClient<'_>, Address) {
+    env.mock_all_auths();
+    let contract_id = env.register(DonationContract, ());
+    let client = DonationContractClient::new(env, &contract_id);
+
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+
+    (client, admin)
+}
 
 #[test]
 fn test_initialize() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, DonationContract);
-    let client = DonationContractClient::new(&env, &contract_id);
-
-    let admin = Address::generate(&env);
-    client.initialize(&admin);
+    let (client, admin) = setup(&env);
 
+    assert_eq!(client.get_admin(), admin);
     assert_eq!(client.get_campaigns_count(), 0);
-    assert_eq!(client.get_donations_count(), 0);
+    assert_eq!(client.get_total_donations_count(), 0);
 }
 
 #[test]
 fn test_create_campaign() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, DonationContract);
-    let client = DonationContractClient::new(&env, &contract_id);
-
-    let admin = Address::generate(&env);
+    let (client, _) = setup(&env);
     let ngo = Address::generate(&env);
 
-    client.initialize(&admin);
-
-    let title = String::from_str(&env, "Clean Water Project");
-    let description = String::from_str(&env, "Providing clean water to communities");
-    let target = 100_000_0000000i128; // 100,000 stroops
+    let name = String::from_str(&env, "Clean Water Project");
+    let goal = 1_000_000_000_000i128;
 
-    let campaign_id = client.create_campaign(&ngo, &title, &description, &target);
+    let campaign_id = client.create_campaign(&ngo, &name, &goal, &DEADLINE);
 
     assert_eq!(campaign_id, 1);
     assert_eq!(client.get_campaigns_count(), 1);
 
     let campaign = client.get_campaign(&campaign_id);
     assert_eq!(campaign.id, 1);
-    assert_eq!(campaign.target_amount, target);
-    assert_eq!(campaign.raised_amount, 0);
-    assert_eq!(campaign.is_active, true);
+    assert_eq!(campaign.org, ngo);
+    assert_eq!(campaign.goal, goal);
+    assert_eq!(campaign.raised, 0);
+    assert!(campaign.active);
 }
 
 #[test]
 fn test_donate() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, DonationContract);
-    let client = DonationContractClient::new(&env, &contract_id);
+    let (client, _) = setup(&env);
+    let ngo = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    let name = String::from_str(&env, "Education Fund");
+    let campaign_id = client.create_campaign(&ngo, &name, &500_000_000_000i128, &DEADLINE);
 
-    let admin = Address::generate(&env);
+    let donation_amount = 10_000_000_000i128;
+    client.donate(&campaign_id, &donor, &donation_amount);
+
+    assert_eq!(client.get_total_donations_count(), 1);
+
+    let campaign = client.get_campaign(&campaign_id);
+    assert_eq!(campaign.raised, donation_amount);
+
+    let donations = client.get_donations(&campaign_id);
+    assert_eq!(donations.len(), 1);
+    assert_eq!(donations.get(0).unwrap().donor, donor);
+    assert_eq!(donations.get(0).unwrap().amount, donation_amount);
+}
+
+#[test]
+fn test_disburse_records_evidence() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
     let ngo = Address::generate(&env);
     let donor = Address::generate(&env);
-    let token = Address::generate(&env);
+    let recipient = Address::generate(&env);
 
-    client.initialize(&admin);
+    let name = String::from_str(&env, "Relief Fund");
+    let campaign_id = client.create_campaign(&ngo, &name, &1_000_000_000_000i128, &DEADLINE);
+
+    let donation_amount = 100_000_000_000i128;
+    client.donate(&campaign_id, &donor, &donation_amount);
+
+    let disbursement_amount = 50_000_000_000i128;
+    let purpose = String::from_str(&env, "Medical supplies");
+    let invoice = BytesN::from_array(&env, &[1u8; 32]);
+    let photo = BytesN::from_array(&env, &[2u8; 32]);
+    client.disburse(
+        &campaign_id,
+        &recipient,
+        &disbursement_amount,
+        &purpose,
+        &vec![&env, invoice.clone(), photo.clone()],
+    );
+
+    let campaign = client.get_campaign(&campaign_id);
+    assert_eq!(campaign.raised, donation_amount - disbursement_amount);
+
+    let disbursements = client.get_campaign_disbursements(&campaign_id);
+    assert_eq!(disbursements.len(), 1);
+    let disbursement = disbursements.get(0).unwrap();
+    assert_eq!(disbursement.recipient, recipient);
+    assert_eq!(disbursement.amount, disbursement_amount);
+    assert_eq!(disbursement.purpose, purpose);
+    assert_eq!(disbursement.evidence, vec![&env, invoice, photo]);
+}
 
-    let title = String::from_str(&env, "Education Fund");
-    let description = String::from_str(&env, "Supporting education");
-    let target = 50_000_0000000i128;
+#[test]
+#[should_panic(expected = "At least one evidence hash is required")]
+fn test_disburse_requires_evidence() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let ngo = Address::generate(&env);
+    let donor = Address::generate(&env);
+    let recipient = Address::generate(&env);
 
-    let campaign_id = client.create_campaign(&ngo, &title, &description, &target);
+    let name = String::from_str(&env, "Relief Fund");
+    let campaign_id = client.create_campaign(&ngo, &name, &1_000_000_000_000i128, &DEADLINE);
+    client.donate(&campaign_id, &donor, &100_000_000_000i128);
 
-    let donation_amount = 1_000_0000000i128;
-    let message = String::from_str(&env, "Keep up the good work!");
+    let purpose = String::from_str(&env, "Medical supplies");
+    client.disburse(&campaign_id, &recipient, &10_000_000_000i128, &purpose, &Vec::new(&env));
+}
 
-    let donation_id = client.donate(&campaign_id, &donor, &donation_amount, &token, &message);
+#[test]
+fn test_close_campaign() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let ngo = Address::generate(&env);
 
-    assert_eq!(donation_id, 1);
-    assert_eq!(client.get_donations_count(), 1);
+    let name = String::from_str(&env, "Test Campaign");
+    let campaign_id = client.create_campaign(&ngo, &name, &100_000_000_000i128, &DEADLINE);
+
+    client.close_campaign(&campaign_id);
 
     let campaign = client.get_campaign(&campaign_id);
-    assert_eq!(campaign.raised_amount, donation_amount);
+    assert!(!campaign.active);
+}
+
+#[test]
+fn test_disburse_after_close() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let campaign_id = funded_campaign(&env, &client);
+
+    // Closing only stops donations; the org still pays out what was raised
+    client.close_campaign(&campaign_id);
+    disburse_with_evidence(&env, &client, campaign_id, 100_000_000_000i128);
+    assert_eq!(client.get_campaign(&campaign_id).raised, 0);
+}
+
+fn funded_campaign(env: &Env, client: &DonationContractClient) -> u32 {
+    let ngo = Address::generate(env);
+    let donor = Address::generate(env);
+    let name = String::from_str(env, "Relief Fund");
+    let campaign_id = client.create_campaign(&ngo, &name, &1_000_000_000_000i128, &DEADLINE);
+    client.donate(&campaign_id, &donor, &100_000_000_000i128);
+    campaign_id
+}
+
+fn disburse_with_evidence(env: &Env, client: &DonationContractClient, campaign_id: u32, amount: i128) {
+    let recipient = Address::generate(env);
+    let purpose = String::from_str(env, "Medical supplies");
+    let evidence = vec![env, BytesN::from_array(env, &[1u8; 32])];
+    client.disburse(&campaign_id, &recipient, &amount, &purpose, &evidence);
+}
+
+#[test]
+#[should_panic(expected = "Campaign is under dispute")]
+fn test_dispute_blocks_disbursement() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let auditor = Address::generate(&env);
+    client.add_auditor(&auditor);
+
+    let campaign_id = funded_campaign(&env, &client);
+    client.open_dispute(&campaign_id, &auditor, &BytesN::from_array(&env, &[9u8; 32]));
+    assert!(client.is_campaign_disputed(&campaign_id));
+
+    disburse_with_evidence(&env, &client, campaign_id, 10_000_000_000i128);
+}
+
+#[test]
+fn test_admin_resolves_dispute() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let auditor = Address::generate(&env);
+    client.add_auditor(&auditor);
+
+    let campaign_id = funded_campaign(&env, &client);
+    let reason_hash = BytesN::from_array(&env, &[9u8; 32]);
+    client.open_dispute(&campaign_id, &auditor, &reason_hash);
+    client.resolve_dispute(&campaign_id);
+
+    let dispute = client.get_dispute(&campaign_id).unwrap();
+    assert!(dispute.resolved);
+    assert_eq!(dispute.opened_by, auditor);
+    assert_eq!(dispute.reason_hash, reason_hash);
+    assert!(!client.is_campaign_disputed(&campaign_id));
+
+    disburse_with_evidence(&env, &client, campaign_id, 10_000_000_000i128);
+    assert_eq!(client.get_campaign(&campaign_id).raised, 90_000_000_000i128);
+}
+
+#[test]
+fn test_auditor_quorum_resolves_dispute() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let auditor1 = Address::generate(&env);
+    let auditor2 = Address::generate(&env);
+    client.add_auditor(&auditor1);
+    client.add_auditor(&auditor2);
+    assert_eq!(client.get_auditor_quorum(), 2);
+
+    let campaign_id = funded_campaign(&env, &client);
+    client.open_dispute(&campaign_id, &auditor1, &BytesN::from_array(&env, &[9u8; 32]));
+
+    client.vote_resolve_dispute(&campaign_id, &auditor1);
+    assert!(client.is_campaign_disputed(&campaign_id));
+
+    client.vote_resolve_dispute(&campaign_id, &auditor2);
+    assert!(!client.is_campaign_disputed(&campaign_id));
+    assert_eq!(client.get_dispute(&campaign_id).unwrap().resolution_votes.len(), 2);
+}
+
+#[test]
+fn test_dispute_history_is_kept() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let auditor = Address::generate(&env);
+    client.add_auditor(&auditor);
+
+    let campaign_id = funded_campaign(&env, &client);
+    client.open_dispute(&campaign_id, &auditor, &BytesN::from_array(&env, &[1u8; 32]));
+    client.resolve_dispute(&campaign_id);
+    client.open_dispute(&campaign_id, &auditor, &BytesN::from_array(&env, &[2u8; 32]));
+
+    assert_eq!(client.get_dispute_count(&campaign_id), 2);
+    let first = client.get_dispute_at(&campaign_id, &1).unwrap();
+    assert!(first.resolved);
+    assert_eq!(first.reason_hash, BytesN::from_array(&env, &[1u8; 32]));
+
+    let latest = client.get_dispute(&campaign_id).unwrap();
+    assert_eq!(latest.sequence, 2);
+    assert!(!latest.resolved);
+    assert!(client.is_campaign_disputed(&campaign_id));
+    assert!(client.get_dispute_at(&campaign_id, &3).is_none());
+}
+
+#[test]
+#[should_panic(expected = "Quorum exceeds the number of auditors")]
+fn test_auditor_quorum_cannot_exceed_auditors() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    client.add_auditor(&Address::generate(&env));
+    client.add_auditor(&Address::generate(&env));
+
+    client.set_auditor_quorum(&2);
+    client.set_auditor_quorum(&3);
+}
 
-    let donation = client.get_donation(&donation_id);
-    assert_eq!(donation.amount, donation_amount);
-    assert_eq!(donation.campaign_id, campaign_id);
+#[test]
+#[should_panic(expected = "Quorum must be greater than 0")]
+fn test_auditor_quorum_cannot_be_zero() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    client.add_auditor(&Address::generate(&env));
+
+    client.set_auditor_quorum(&0);
 }
 
 #[test]
-fn test_multisig_disbursement() {
+#[should_panic(expected = "Caller is not an auditor")]
+fn test_non_auditor_cannot_open_dispute() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, DonationContract);
-    let client = DonationContractClient::new(&env, &contract_id);
+    let (client, _) = setup(&env);
+    let stranger = Address::generate(&env);
 
-    let admin = Address::generate(&env);
+    let campaign_id = funded_campaign(&env, &client);
+    client.open_dispute(&campaign_id, &stranger, &BytesN::from_array(&env, &[9u8; 32]));
+}
+
+#[test]
+fn test_donor_totals_and_campaign_index() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
     let ngo = Address::generate(&env);
     let donor = Address::generate(&env);
-    let recipient = Address::generate(&env);
-    let approver1 = Address::generate(&env);
-    let approver2 = Address::generate(&env);
-    let token = Address::generate(&env);
+    let other_donor = Address::generate(&env);
 
-    client.initialize(&admin);
-    client.set_multisig_requirement(&admin, &2);
+    let water = client.create_campaign(&ngo, &String::from_str(&env, "Water"), &1_000_000i128, &DEADLINE);
+    let school = client.create_campaign(&ngo, &String::from_str(&env, "School"), &1_000_000i128, &DEADLINE);
+
+    client.donate(&water, &donor, &100i128);
+    client.donate(&school, &donor, &250i128);
+    client.donate(&water, &donor, &50i128);
+    client.donate(&school, &other_donor, &75i128);
+
+    assert_eq!(client.get_donor_total(&donor), 400i128);
+    assert_eq!(client.get_donor_campaigns(&donor), vec![&env, water, school]);
+    assert_eq!(client.get_donor_total(&other_donor), 75i128);
+    assert_eq!(client.get_donor_campaigns(&other_donor), vec![&env, school]);
+    assert_eq!(client.get_donor_total(&ngo), 0i128);
+    assert!(client.get_donor_campaigns(&ngo).is_empty());
+}
 
-    let title = String::from_str(&env, "Relief Fund");
-    let description = String::from_str(&env, "Disaster relief");
-    let target = 100_000_0000000i128;
+#[test]
+fn test_list_campaigns_paginates_and_filters() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let ngo = Address::generate(&env);
 
-    let campaign_id = client.create_campaign(&ngo, &title, &description, &target);
+    for _ in 0..5 {
+        client.create_campaign(&ngo, &String::from_str(&env, "Campaign"), &1_000i128, &DEADLINE);
+    }
+    client.close_campaign(&2);
+    client.close_campaign(&4);
 
-    // Make donation
-    let donation_amount = 10_000_0000000i128;
-    let message = String::from_str(&env, "Help those in need");
-    client.donate(&campaign_id, &donor, &donation_amount, &token, &message);
+    let first_page = client.list_campaigns(&0, &2, &None);
+    assert_eq!(first_page.campaigns.len(), 2);
+    assert_eq!(first_page.campaigns.get(0).unwrap().id, 1);
+    assert_eq!(first_page.campaigns.get(1).unwrap().id, 2);
+    assert_eq!(first_page.next_start, 3);
 
-    // Propose disbursement
-    let disbursement_amount = 5_000_0000000i128;
-    let disbursement_desc = String::from_str(&env, "Medical supplies");
-    let disbursement_id = client.propose_disbursement(
-        &campaign_id,
-        &recipient,
-        &disbursement_amount,
-        &disbursement_desc,
-        &approver1,
+    let second_page = client.list_campaigns(&3, &2, &None);
+    assert_eq!(second_page.campaigns.len(), 2);
+    assert_eq!(second_page.campaigns.get(0).unwrap().id, 3);
+
+    let active = client.list_campaigns(&1, &10, &Some(true));
+    assert_eq!(active.campaigns.len(), 3);
+    assert_eq!(active.campaigns.get(2).unwrap().id, 5);
+    assert_eq!(active.next_start, 0);
+
+    let closed = client.list_campaigns(&1, &10, &Some(false));
+    assert_eq!(closed.campaigns.len(), 2);
+    assert_eq!(closed.campaigns.get(1).unwrap().id, 4);
+
+    assert!(client.list_campaigns(&6, &10, &None).campaigns.is_empty());
+}
+
+#[test]
+fn test_list_campaigns_bounds_entries_scanned() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let ngo = Address::generate(&env);
+
+    for _ in 0..MAX_SCAN + 5 {
+        client.create_campaign(&ngo, &String::from_str(&env, "Campaign"), &1_000i128, &DEADLINE);
+    }
+    client.close_campaign(&(MAX_SCAN + 3));
+
+    let first = client.list_campaigns(&1, &10, &Some(false));
+    assert!(first.campaigns.is_empty());
+    assert_eq!(first.next_start, MAX_SCAN + 1);
+
+    let second = client.list_campaigns(&first.next_start, &10, &Some(false));
+    assert_eq!(second.campaigns.len(), 1);
+    assert_eq!(second.campaigns.get(0).unwrap().id, MAX_SCAN + 3);
+    assert_eq!(second.next_start, 0);
+}
+
+#[test]
+fn test_get_org_campaigns() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let ngo = Address::generate(&env);
+    let other_ngo = Address::generate(&env);
+
+    let first = client.create_campaign(&ngo, &String::from_str(&env, "Water"), &1_000i128, &DEADLINE);
+    client.create_campaign(&other_ngo, &String::from_str(&env, "Food"), &1_000i128, &DEADLINE);
+    let second = client.create_campaign(&ngo, &String::from_str(&env, "School"), &1_000i128, &DEADLINE);
+    let third = client.create_campaign(&ngo, &String::from_str(&env, "Clinic"), &1_000i128, &DEADLINE);
+
+    let campaigns = client.get_org_campaigns(&ngo, &0, &2);
+    assert_eq!(campaigns.len(), 2);
+    assert_eq!(campaigns.get(0).unwrap().id, first);
+    assert_eq!(campaigns.get(1).unwrap().id, second);
+
+    let rest = client.get_org_campaigns(&ngo, &2, &2);
+    assert_eq!(rest.len(), 1);
+    assert_eq!(rest.get(0).unwrap().id, third);
+    assert!(client.get_org_campaigns(&ngo, &3, &2).is_empty());
+    assert_eq!(client.get_org_campaigns(&other_ngo, &0, &10).len(), 1);
+}
+
+fn over_raised_campaigns(env: &Env, client: &DonationContractClient, donors: &[(&Address, i128)]) -> (u32, u32) {
+    let ngo = Address::generate(env);
+    let source = client.create_campaign(&ngo, &String::from_str(env, "Flood Relief"), &1_000i128, &DEADLINE);
+    let target = client.create_campaign(&ngo, &String::from_str(env, "Rebuilding"), &5_000i128, &DEADLINE);
+    for (donor, amount) in donors {
+        client.donate(&source, donor, amount);
+    }
+    (source, target)
+}
+
+#[test]
+fn test_surplus_transfer_with_admin_approval() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let donor = Address::generate(&env);
+    let (source, target) = over_raised_campaigns(&env, &client, &[(&donor, 1_500i128)]);
+
+    let transfer_id = client.propose_surplus_transfer(&source, &target, &400i128);
+    client.approve_surplus_transfer(&transfer_id);
+    client.execute_surplus_transfer(&transfer_id);
+
+    assert_eq!(client.get_campaign(&source).raised, 1_100i128);
+    assert_eq!(client.get_campaign(&target).raised, 400i128);
+    assert!(client.get_surplus_transfer(&transfer_id).executed);
+}
+
+#[test]
+fn test_surplus_transfer_with_donor_consent() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let major_donor = Address::generate(&env);
+    let minor_donor = Address::generate(&env);
+    let (source, target) =
+        over_raised_campaigns(&env, &client, &[(&major_donor, 1_200i128), (&minor_donor, 300i128)]);
+
+    let transfer_id = client.propose_surplus_transfer(&source, &target, &500i128);
+    client.consent_surplus_transfer(&transfer_id, &major_donor);
+    assert_eq!(client.get_surplus_transfer(&transfer_id).consent_weight, 1_200i128);
+
+    client.execute_surplus_transfer(&transfer_id);
+    assert_eq!(client.get_campaign(&source).raised, 1_000i128);
+    assert_eq!(client.get_campaign(&target).raised, 500i128);
+}
+
+#[test]
+#[should_panic(expected = "Surplus transfer not approved")]
+fn test_surplus_transfer_requires_majority_consent() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let major_donor = Address::generate(&env);
+    let minor_donor = Address::generate(&env);
+    let (source, target) =
+        over_raised_campaigns(&env, &client, &[(&major_donor, 1_200i128), (&minor_donor, 300i128)]);
+
+    let transfer_id = client.propose_surplus_transfer(&source, &target, &500i128);
+    client.consent_surplus_transfer(&transfer_id, &minor_donor);
+    client.execute_surplus_transfer(&transfer_id);
+}
+
+#[test]
+#[should_panic(expected = "Consent window has closed")]
+fn test_surplus_consent_after_window_rejected() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let donor = Address::generate(&env);
+    let (source, target) = over_raised_campaigns(&env, &client, &[(&donor, 1_500i128)]);
+
+    let transfer_id = client.propose_surplus_transfer(&source, &target, &500i128);
+    env.ledger().with_mut(|ledger| ledger.timestamp = CONSENT_WINDOW + 1);
+    client.consent_surplus_transfer(&transfer_id, &donor);
+}
+
+#[test]
+#[should_panic(expected = "Amount exceeds campaign surplus")]
+fn test_surplus_transfer_limited_to_surplus() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let donor = Address::generate(&env);
+    let (source, target) = over_raised_campaigns(&env, &client, &[(&donor, 1_500i128)]);
+
+    client.propose_surplus_transfer(&source, &target, &501i128);
+}
+
+#[test]
+#[should_panic(expected = "Campaign is not active")]
+fn test_surplus_transfer_from_closed_campaign_rejected() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let donor = Address::generate(&env);
+    let (source, target) = over_raised_campaigns(&env, &client, &[(&donor, 1_500i128)]);
+
+    client.close_campaign(&source);
+    client.propose_surplus_transfer(&source, &target, &400i128);
+}
+
+#[test]
+#[should_panic(expected = "Campaign is not active")]
+fn test_surplus_transfer_to_closed_campaign_not_executed() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let donor = Address::generate(&env);
+    let (source, target) = over_raised_campaigns(&env, &client, &[(&donor, 1_500i128)]);
+
+    let transfer_id = client.propose_surplus_transfer(&source, &target, &400i128);
+    client.approve_surplus_transfer(&transfer_id);
+    client.close_campaign(&target);
+    client.execute_surplus_transfer(&transfer_id);
+}
+
+#[test]
+#[should_panic(expected = "Campaign is under dispute")]
+fn test_surplus_transfer_to_disputed_campaign_not_executed() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let auditor = Address::generate(&env);
+    client.add_auditor(&auditor);
+    let donor = Address::generate(&env);
+    let (source, target) = over_raised_campaigns(&env, &client, &[(&donor, 1_500i128)]);
+
+    let transfer_id = client.propose_surplus_transfer(&source, &target, &400i128);
+    client.approve_surplus_transfer(&transfer_id);
+    client.open_dispute(&target, &auditor, &BytesN::from_array(&env, &[9u8; 32]));
+    client.execute_surplus_transfer(&transfer_id);
+}
+
+fn limited_campaign(env: &Env, client: &DonationContractClient, limits: &DonationLimits) -> u32 {
+    let ngo = Address::generate(env);
+    let campaign_id = client.create_campaign(&ngo, &String::from_str(env, "Shelter"), &1_000i128, &DEADLINE);
+    client.set_donation_limits(&campaign_id, limits);
+    campaign_id
+}
+
+#[test]
+fn test_donation_limits_minimum_and_donor_cap() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let donor = Address::generate(&env);
+    let limits = DonationLimits {
+        min_donation: Some(10i128),
+        max_per_donor: Some(300i128),
+        overflow: OverflowPolicy::Unlimited,
+    };
+    let campaign_id = limited_campaign(&env, &client, &limits);
+    assert_eq!(client.get_donation_limits(&campaign_id), limits);
+
+    assert_eq!(
+        client.try_donate(&campaign_id, &donor, &9i128),
+        Err(Ok(DonationError::BelowMinimumDonation))
+    );
+    client.donate(&campaign_id, &donor, &200i128);
+    assert_eq!(
+        client.try_donate(&campaign_id, &donor, &101i128),
+        Err(Ok(DonationError::DonorCapExceeded))
     );
+    client.donate(&campaign_id, &donor, &100i128);
+    assert_eq!(client.get_campaign(&campaign_id).raised, 300i128);
+}
 
-    assert_eq!(disbursement_id, 1);
+#[test]
+fn test_overflow_policy_reject_at_goal() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let donor = Address::generate(&env);
+    let limits = DonationLimits {
+        min_donation: None,
+        max_per_donor: None,
+        overflow: OverflowPolicy::RejectAtGoal,
+    };
+    let campaign_id = limited_campaign(&env, &client, &limits);
 
-    // Second approval
-    client.approve_disbursement(&disbursement_id, &approver2);
+    client.donate(&campaign_id, &donor, &1_200i128);
+    assert_eq!(
+        client.try_donate(&campaign_id, &donor, &1i128),
+        Err(Ok(DonationError::GoalReached))
+    );
+}
 
-    // Execute disbursement
-    client.execute_disbursement(&disbursement_id);
+#[test]
+fn test_overflow_policy_hard_cap() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let donor = Address::generate(&env);
+    let limits = DonationLimits {
+        min_donation: None,
+        max_per_donor: None,
+        overflow: OverflowPolicy::HardCap(1_500i128),
+    };
+    let campaign_id = limited_campaign(&env, &client, &limits);
 
-    let disbursement = client.get_disbursement(&disbursement_id);
-    assert_eq!(disbursement.executed, true);
-    assert_eq!(disbursement.approved_by.len(), 2);
+    client.donate(&campaign_id, &donor, &1_400i128);
+    assert_eq!(
+        client.try_donate(&campaign_id, &donor, &101i128),
+        Err(Ok(DonationError::HardCapExceeded))
+    );
+    client.donate(&campaign_id, &donor, &100i128);
+    assert_eq!(client.get_campaign(&campaign_id).raised, 1_500i128);
+}
 
-    let campaign = client.get_campaign(&campaign_id);
-    assert_eq!(campaign.raised_amount, donation_amount - disbursement_amount);
-    assert_eq!(campaign.recipient_count, 1);
+#[test]
+fn test_overflow_policy_counts_disbursed_funds() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let donor = Address::generate(&env);
+    let limits = DonationLimits {
+        min_donation: None,
+        max_per_donor: None,
+        overflow: OverflowPolicy::HardCap(1_500i128),
+    };
+    let campaign_id = limited_campaign(&env, &client, &limits);
+
+    client.donate(&campaign_id, &donor, &1_400i128);
+    disburse_with_evidence(&env, &client, campaign_id, 1_000i128);
+    assert_eq!(client.get_campaign(&campaign_id).raised, 400i128);
+    assert_eq!(client.get_total_raised(&campaign_id), 1_400i128);
+
+    // Spending funds does not make room under the cap
+    assert_eq!(
+        client.try_donate(&campaign_id, &donor, &101i128),
+        Err(Ok(DonationError::HardCapExceeded))
+    );
+    client.donate(&campaign_id, &donor, &100i128);
+
+    let goal_limits = DonationLimits {
+        min_donation: None,
+        max_per_donor: None,
+        overflow: OverflowPolicy::RejectAtGoal,
+    };
+    let goal_campaign = limited_campaign(&env, &client, &goal_limits);
+    client.donate(&goal_campaign, &donor, &1_000i128);
+    disburse_with_evidence(&env, &client, goal_campaign, 1_000i128);
+    assert_eq!(
+        client.try_donate(&goal_campaign, &donor, &1i128),
+        Err(Ok(DonationError::GoalReached))
+    );
 }
 
 #[test]
-fn test_close_campaign() {
+fn test_invalid_donation_limits_rejected() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, DonationContract);
-    let client = DonationContractClient::new(&env, &contract_id);
+    let (client, _) = setup(&env);
+    let ngo = Address::generate(&env);
+    let campaign_id = client.create_campaign(&ngo, &String::from_str(&env, "Shelter"), &1_000i128, &DEADLINE);
+
+    let limits = DonationLimits {
+        min_donation: None,
+        max_per_donor: None,
+        overflow: OverflowPolicy::HardCap(999i128),
+    };
+    assert_eq!(
+        client.try_set_donation_limits(&campaign_id, &limits),
+        Err(Ok(DonationError::InvalidLimits))
+    );
+}
 
-    let admin = Address::generate(&env);
+#[test]
+fn test_donate_with_receipt() {
+    let env = Env::default();
+    env.ledger().with_mut(|ledger| ledger.timestamp = 1_000);
+    let (client, _) = setup(&env);
     let ngo = Address::generate(&env);
+    let donor = Address::generate(&env);
+    let campaign_id = client.create_campaign(&ngo, &String::from_str(&env, "Clinic"), &1_000i128, &DEADLINE);
 
-    client.initialize(&admin);
+    client.donate(&campaign_id, &donor, &50i128);
+    let receipt_id = client.donate_with_receipt(&campaign_id, &donor, &250i128);
+    assert_eq!(receipt_id, 1);
 
-    let title = String::from_str(&env, "Test Campaign");
-    let description = String::from_str(&env, "Test");
-    let target = 10_000_0000000i128;
+    let receipt = client.verify_receipt(&receipt_id).unwrap();
+    assert_eq!(
+        receipt,
+        DonationReceipt {
+            id: 1,
+            donor: donor.clone(),
+            campaign_id,
+            amount: 250i128,
+            timestamp: 1_000,
+        }
+    );
+    assert_eq!(client.get_campaign(&campaign_id).raised, 300i128);
+    assert_eq!(client.get_donor_total(&donor), 300i128);
+    assert_eq!(client.verify_receipt(&2), None);
+}
 
-    let campaign_id = client.create_campaign(&ngo, &title, &description, &target);
+#[test]
+fn test_receipt_not_issued_when_donation_rejected() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let donor = Address::generate(&env);
+    let limits = DonationLimits {
+        min_donation: Some(100i128),
+        max_per_donor: None,
+        overflow: OverflowPolicy::Unlimited,
+    };
+    let campaign_id = limited_campaign(&env, &client, &limits);
 
-    client.close_campaign(&campaign_id, &ngo);
+    assert_eq!(
+        client.try_donate_with_receipt(&campaign_id, &donor, &10i128),
+        Err(Ok(DonationError::BelowMinimumDonation))
+    );
+    assert_eq!(client.verify_receipt(&1), None);
+}
 
-    let campaign = client.get_campaign(&campaign_id);
-    assert_eq!(campaign.is_active, false);
+#[test]
+fn test_voucher_allocate_spend_redeem() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let beneficiary = Address::generate(&env);
+    let merchant = Address::generate(&env);
+
+    let campaign_id = funded_campaign(&env, &client);
+    client.register_merchant(&campaign_id, &merchant);
+    assert_eq!(client.get_campaign_merchants(&campaign_id), vec![&env, merchant.clone()]);
+
+    client.allocate_voucher(&campaign_id, &beneficiary, &1_000i128);
+    assert_eq!(client.get_campaign(&campaign_id).raised, 100_000_000_000i128 - 1_000i128);
+    assert_eq!(client.get_voucher_balance(&campaign_id, &beneficiary), 1_000i128);
+
+    client.spend_voucher(&campaign_id, &beneficiary, &merchant, &600i128);
+    assert_eq!(client.get_voucher_balance(&campaign_id, &beneficiary), 400i128);
+    assert_eq!(client.get_merchant_balance(&campaign_id, &merchant), 600i128);
+
+    client.redeem_voucher(&campaign_id, &merchant, &600i128);
+    assert_eq!(client.get_merchant_balance(&campaign_id, &merchant), 0i128);
+}
+
+#[test]
+#[should_panic(expected = "Merchant not registered for campaign")]
+fn test_voucher_spend_at_unregistered_merchant() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let beneficiary = Address::generate(&env);
+    let merchant = Address::generate(&env);
+
+    let campaign_id = funded_campaign(&env, &client);
+    client.allocate_voucher(&campaign_id, &beneficiary, &1_000i128);
+    client.spend_voucher(&campaign_id, &beneficiary, &merchant, &100i128);
+}
+
+#[test]
+#[should_panic(expected = "Insufficient voucher balance")]
+fn test_voucher_overspend() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let beneficiary = Address::generate(&env);
+    let merchant = Address::generate(&env);
+
+    let campaign_id = funded_campaign(&env, &client);
+    client.register_merchant(&campaign_id, &merchant);
+    client.allocate_voucher(&campaign_id, &beneficiary, &1_000i128);
+    client.spend_voucher(&campaign_id, &beneficiary, &merchant, &1_001i128);
+}
+
+#[test]
+#[should_panic(expected = "Insufficient merchant balance")]
+fn test_voucher_redeem_more_than_spent() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let beneficiary = Address::generate(&env);
+    let merchant = Address::generate(&env);
+
+    let campaign_id = funded_campaign(&env, &client);
+    client.register_merchant(&campaign_id, &merchant);
+    client.allocate_voucher(&campaign_id, &beneficiary, &1_000i128);
+    client.spend_voucher(&campaign_id, &beneficiary, &merchant, &300i128);
+    client.redeem_voucher(&campaign_id, &merchant, &301i128);
+}
+
+#[test]
+#[should_panic(expected = "Campaign is not active")]
+fn test_voucher_allocation_from_closed_campaign_rejected() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let beneficiary = Address::generate(&env);
+
+    let campaign_id = funded_campaign(&env, &client);
+    client.close_campaign(&campaign_id);
+    client.allocate_voucher(&campaign_id, &beneficiary, &1_000i128);
+}
+
+#[test]
+#[should_panic(expected = "Campaign is under dispute")]
+fn test_voucher_spend_blocked_while_disputed() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let auditor = Address::generate(&env);
+    client.add_auditor(&auditor);
+    let beneficiary = Address::generate(&env);
+    let merchant = Address::generate(&env);
+
+    let campaign_id = funded_campaign(&env, &client);
+    client.register_merchant(&campaign_id, &merchant);
+    client.allocate_voucher(&campaign_id, &beneficiary, &1_000i128);
+    client.open_dispute(&campaign_id, &auditor, &BytesN::from_array(&env, &[9u8; 32]));
+    client.spend_voucher(&campaign_id, &beneficiary, &merchant, &100i128);
+}
+
+#[test]
+#[should_panic(expected = "Campaign is under dispute")]
+fn test_voucher_redeem_blocked_while_disputed() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let auditor = Address::generate(&env);
+    client.add_auditor(&auditor);
+    let beneficiary = Address::generate(&env);
+    let merchant = Address::generate(&env);
+
+    let campaign_id = funded_campaign(&env, &client);
+    client.register_merchant(&campaign_id, &merchant);
+    client.allocate_voucher(&campaign_id, &beneficiary, &1_000i128);
+    client.spend_voucher(&campaign_id, &beneficiary, &merchant, &300i128);
+    client.open_dispute(&campaign_id, &auditor, &BytesN::from_array(&env, &[9u8; 32]));
+    client.redeem_voucher(&campaign_id, &merchant, &300i128);
+}
+
+#[test]
+fn test_child_campaign_donations_roll_up_to_parent() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let ngo = Address::generate(&env);
+    let supporter = Address::generate(&env);
+    let donor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    let parent_id = client.create_campaign(&ngo, &String::from_str(&env, "Earthquake Appeal"), &10_000i128, &DEADLINE);
+    let child_id = client.create_child_campaign(
+        &parent_id,
+        &supporter,
+        &String::from_str(&env, "Marathon for Relief"),
+        &1_000i128,
+        &DEADLINE,
+    );
+    assert_eq!(client.get_child_campaigns(&parent_id), vec![&env, child_id]);
+    assert_eq!(client.get_campaign(&child_id).parent, Some(parent_id));
+
+    client.donate(&parent_id, &donor, &500i128);
+    client.donate(&child_id, &donor, &700i128);
+
+    assert_eq!(client.get_campaign(&child_id).raised, 700i128);
+    assert!(!client.is_goal_reached(&child_id));
+    assert_eq!(client.get_campaign(&parent_id).raised, 1_200i128);
+
+    let purpose = String::from_str(&env, "Tents");
+    let evidence = vec![&env, BytesN::from_array(&env, &[3u8; 32])];
+    client.disburse(&parent_id, &recipient, &1_200i128, &purpose, &evidence);
+    assert_eq!(client.get_campaign(&parent_id).raised, 0i128);
+}
+
+#[test]
+#[should_panic(expected = "Sub-campaign funds are held by the parent campaign")]
+fn test_child_campaign_cannot_disburse() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let ngo = Address::generate(&env);
+    let supporter = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    let parent_id = client.create_campaign(&ngo, &String::from_str(&env, "Earthquake Appeal"), &10_000i128, &DEADLINE);
+    let child_id =
+        client.create_child_campaign(&parent_id, &supporter, &String::from_str(&env, "Bake Sale"), &100i128, &DEADLINE);
+    client.donate(&child_id, &donor, &100i128);
+
+    disburse_with_evidence(&env, &client, child_id, 100i128);
+}
+
+#[test]
+#[should_panic(expected = "Parent campaign is not active")]
+fn test_child_campaign_rejects_donations_when_parent_closed() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let ngo = Address::generate(&env);
+    let supporter = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    let parent_id = client.create_campaign(&ngo, &String::from_str(&env, "Earthquake Appeal"), &10_000i128, &DEADLINE);
+    let child_id =
+        client.create_child_campaign(&parent_id, &supporter, &String::from_str(&env, "Bake Sale"), &100i128, &DEADLINE);
+    client.close_campaign(&parent_id);
+
+    client.donate(&child_id, &donor, &100i128);
+}
+
+#[test]
+fn test_child_campaign_donations_respect_parent_limits() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let ngo = Address::generate(&env);
+    let supporter = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    let parent_id = client.create_campaign(&ngo, &String::from_str(&env, "Earthquake Appeal"), &1_000i128, &DEADLINE);
+    let limits = DonationLimits {
+        min_donation: None,
+        max_per_donor: Some(600i128),
+        overflow: OverflowPolicy::HardCap(1_000i128),
+    };
+    client.set_donation_limits(&parent_id, &limits);
+    let child_id =
+        client.create_child_campaign(&parent_id, &supporter, &String::from_str(&env, "Bake Sale"), &5_000i128, &DEADLINE);
+
+    client.donate(&parent_id, &donor, &500i128);
+    assert_eq!(
+        client.try_donate(&child_id, &donor, &200i128),
+        Err(Ok(DonationError::DonorCapExceeded))
+    );
+    client.donate(&child_id, &Address::generate(&env), &500i128);
+    assert_eq!(
+        client.try_donate(&child_id, &Address::generate(&env), &1i128),
+        Err(Ok(DonationError::HardCapExceeded))
+    );
+    assert_eq!(client.get_campaign(&parent_id).raised, 1_000i128);
+}
+
+#[test]
+fn test_child_campaigns_stay_out_of_org_index() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let ngo = Address::generate(&env);
+    let supporter = Address::generate(&env);
+
+    let parent_id = client.create_campaign(&ngo, &String::from_str(&env, "Earthquake Appeal"), &1_000i128, &DEADLINE);
+    client.create_child_campaign(&parent_id, &supporter, &String::from_str(&env, "Bake Sale"), &100i128, &DEADLINE);
+
+    assert_eq!(client.get_org_campaigns(&ngo, &0, &10).len(), 1);
+    assert_eq!(client.get_org_campaigns(&supporter, &0, &10).len(), 0);
+}
+
+#[test]
+#[should_panic(expected = "Surplus transfer not approved")]
+fn test_surplus_consent_weighs_child_donors() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let supporter = Address::generate(&env);
+    let direct_donor = Address::generate(&env);
+    let (source, target) = over_raised_campaigns(&env, &client, &[(&direct_donor, 1_100i128)]);
+    let child_id =
+        client.create_child_campaign(&source, &supporter, &String::from_str(&env, "Bake Sale"), &100i128, &DEADLINE);
+    client.donate(&child_id, &Address::generate(&env), &1_400i128);
+
+    // 1,100 of 2,500 is not a majority once the page's donors count
+    let transfer_id = client.propose_surplus_transfer(&source, &target, &500i128);
+    client.consent_surplus_transfer(&transfer_id, &direct_donor);
+    client.execute_surplus_transfer(&transfer_id);
+}
+
+#[test]
+fn test_child_donors_can_consent_to_parent_surplus() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let supporter = Address::generate(&env);
+    let page_donor = Address::generate(&env);
+    let (source, target) = over_raised_campaigns(&env, &client, &[(&Address::generate(&env), 400i128)]);
+    let child_id =
+        client.create_child_campaign(&source, &supporter, &String::from_str(&env, "Bake Sale"), &100i128, &DEADLINE);
+    client.donate(&child_id, &page_donor, &1_100i128);
+
+    let transfer_id = client.propose_surplus_transfer(&source, &target, &500i128);
+    client.consent_surplus_transfer(&transfer_id, &page_donor);
+    assert_eq!(client.get_surplus_transfer(&transfer_id).consent_weight, 1_100i128);
+    client.execute_surplus_transfer(&transfer_id);
+    assert_eq!(client.get_campaign(&target).raised, 500i128);
+}
+
+fn joint_campaign(env: &Env, client: &DonationContractClient, owners: &Vec<Address>) -> u32 {
+    let co_owners = CoOwners {
+        owners: owners.clone(),
+        shares_bps: vec![env, 5_000u32, 3_000u32, 2_000u32],
+        threshold: 2,
+    };
+    let campaign_id = client.create_joint_campaign(&co_owners, &String::from_str(env, "Joint Appeal"), &10_000i128, &DEADLINE);
+    client.donate(&campaign_id, &Address::generate(env), &10_000i128);
+    campaign_id
+}
+
+#[test]
+fn test_joint_campaign_disburse_close_and_claim() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let owners = vec![&env, Address::generate(&env), Address::generate(&env), Address::generate(&env)];
+    let campaign_id = joint_campaign(&env, &client, &owners);
+    assert_eq!(client.get_co_owners(&campaign_id).unwrap().threshold, 2);
+    assert_eq!(client.get_org_campaigns(&owners.get(2).unwrap(), &0, &10).len(), 1);
+
+    let signers = vec![&env, owners.get(0).unwrap(), owners.get(2).unwrap()];
+    let purpose = String::from_str(&env, "Water trucks");
+    let evidence = vec![&env, BytesN::from_array(&env, &[4u8; 32])];
+    client.disburse_joint(&campaign_id, &signers, &Address::generate(&env), &2_000i128, &purpose, &evidence);
+    assert_eq!(client.get_campaign(&campaign_id).raised, 8_000i128);
+
+    client.close_joint_campaign(&campaign_id, &signers);
+    assert!(!client.get_campaign(&campaign_id).active);
+
+    assert_eq!(client.claim_share(&campaign_id, &owners.get(0).unwrap()), 4_000i128);
+    assert_eq!(client.claim_share(&campaign_id, &owners.get(1).unwrap()), 2_400i128);
+    assert_eq!(client.claim_share(&campaign_id, &owners.get(2).unwrap()), 1_600i128);
+    assert_eq!(client.get_campaign(&campaign_id).raised, 0i128);
+}
+
+#[test]
+#[should_panic(expected = "Not enough owner signatures")]
+fn test_joint_campaign_disburse_below_threshold() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let owners = vec![&env, Address::generate(&env), Address::generate(&env), Address::generate(&env)];
+    let campaign_id = joint_campaign(&env, &client, &owners);
+
+    let signers = vec![&env, owners.get(1).unwrap()];
+    let purpose = String::from_str(&env, "Water trucks");
+    let evidence = vec![&env, BytesN::from_array(&env, &[4u8; 32])];
+    client.disburse_joint(&campaign_id, &signers, &Address::generate(&env), &2_000i128, &purpose, &evidence);
+}
+
+#[test]
+#[should_panic(expected = "Joint campaign requires owner signatures")]
+fn test_joint_campaign_rejects_single_owner_close() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let owners = vec![&env, Address::generate(&env), Address::generate(&env), Address::generate(&env)];
+    let campaign_id = joint_campaign(&env, &client, &owners);
+
+    client.close_campaign(&campaign_id);
+}
+
+#[test]
+#[should_panic(expected = "Share already claimed")]
+fn test_joint_campaign_share_claimed_once() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let owners = vec![&env, Address::generate(&env), Address::generate(&env), Address::generate(&env)];
+    let campaign_id = joint_campaign(&env, &client, &owners);
+    client.close_joint_campaign(&campaign_id, &vec![&env, owners.get(0).unwrap(), owners.get(1).unwrap()]);
+
+    client.claim_share(&campaign_id, &owners.get(0).unwrap());
+    client.claim_share(&campaign_id, &owners.get(0).unwrap());
+}
+
+#[test]
+#[should_panic(expected = "Campaign is not active")]
+fn test_joint_campaign_disburse_after_close_rejected() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let owners = vec![&env, Address::generate(&env), Address::generate(&env), Address::generate(&env)];
+    let campaign_id = joint_campaign(&env, &client, &owners);
+    let signers = vec![&env, owners.get(0).unwrap(), owners.get(1).unwrap()];
+    client.close_joint_campaign(&campaign_id, &signers);
+
+    let purpose = String::from_str(&env, "Water trucks");
+    let evidence = vec![&env, BytesN::from_array(&env, &[4u8; 32])];
+    client.disburse_joint(&campaign_id, &signers, &Address::generate(&env), &2_000i128, &purpose, &evidence);
+}
+
+#[test]
+fn test_joint_campaign_claim_blocked_while_disputed() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let auditor = Address::generate(&env);
+    let owners = vec![&env, Address::generate(&env), Address::generate(&env), Address::generate(&env)];
+    let campaign_id = joint_campaign(&env, &client, &owners);
+    client.close_joint_campaign(&campaign_id, &vec![&env, owners.get(0).unwrap(), owners.get(1).unwrap()]);
+
+    client.add_auditor(&auditor);
+    client.open_dispute(&campaign_id, &auditor, &BytesN::from_array(&env, &[9u8; 32]));
+    assert_eq!(
+        client.try_claim_share(&campaign_id, &owners.get(0).unwrap()),
+        Err(Ok(DonationError::Disputed))
+    );
+    assert_eq!(client.get_campaign(&campaign_id).raised, 10_000i128);
+
+    client.resolve_dispute(&campaign_id);
+    assert_eq!(client.claim_share(&campaign_id, &owners.get(0).unwrap()), 5_000i128);
 }
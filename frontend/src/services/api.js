@@ -42,7 +42,7 @@ apiClient.interceptors.response.use(
 const api = {
   // Organizations
   organizations: {
-    list: () => apiClient.get('/organizations'),
+    list: (params) => apiClient.get('/organizations', { params }),
     getById: (id) => apiClient.get(`/organizations/${id}`),
     getByWallet: (walletAddress) => apiClient.get(`/organizations/wallet/${walletAddress}`),
     create: (data) => apiClient.post('/organizations', data),
@@ -69,7 +69,7 @@ const api = {
     getById: (id) => apiClient.get(`/disbursements/${id}`),
     approve: (id, data) => apiClient.post(`/disbursements/${id}/approve`, data),
     execute: (id, data) => apiClient.post(`/disbursements/${id}/execute`, data),
-    listByCampaign: (campaignId) => apiClient.get(`/disbursements/campaign/${campaignId}`)
+    listByCampaign: (campaignId, params) => apiClient.get(`/disbursements/campaign/${campaignId}`, { params })
   },
 
   // Audit
@@ -16,8 +16,8 @@ export const useCampaignStore = defineStore('campaign', () => {
     
     try {
       const response = await api.campaigns.list(filters)
-      campaigns.value = response.data
-      return response.data
+      campaigns.value = response.data.items
+      return response.data.items
     } catch (err) {
       error.value = err.message
       console.error('Error fetching campaigns:', err)